@@ -0,0 +1,184 @@
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::classifier::{ClassificationMethod, Classifier};
+use crate::error::ClassifyError;
+use crate::hinge::percentile;
+use crate::metrics;
+use crate::parallel::map_slice;
+use crate::sorted_data::SortedData;
+use crate::standard_deviation::calc_st_dev;
+
+/// Represents a strategy for automatically choosing the number of bins
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AutoStrategy {
+    /// The smallest number of bins whose goodness of variance fit (GVF) is at least the given threshold (between 0 and 1)
+    GvfThreshold(f64),
+    /// The number of bins at the "elbow" of the GVF curve, i.e. the point furthest above the line joining its first and last points
+    GvfElbow,
+    /// Sturges' rule: ceil(log2(n)) + 1
+    Sturges,
+    /// Scott's rule: bins of width 3.49 * standard deviation * n^(-1/3)
+    Scott,
+    /// The Freedman-Diaconis rule: bins of width 2 * IQR * n^(-1/3)
+    FreedmanDiaconis,
+    /// Doane's rule: 1 + log2(n) + log2(1 + |skewness| / standard error of skewness)
+    Doane,
+    /// The Jenks-Caspall rule of thumb: round(5 * log10(n))
+    JenksCaspall,
+}
+
+/// Represents the result of automatically choosing the number of bins
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoSelection {
+    /// The chosen number of bins
+    pub num_bins: usize,
+    /// The classification method configured with the chosen number of bins
+    pub method: ClassificationMethod,
+    /// The goodness of variance fit (GVF) obtained for every number of bins considered, as (number of bins, GVF) pairs
+    pub scores: Vec<(usize, f64)>,
+}
+
+/// Chooses the number of bins for a count-based classification method (Jenks, Quantile, Equal Interval, etc.) given a strategy, the maximum number of bins to consider and a dataset
+///
+/// # Arguments
+///
+/// * `method` - A ClassificationMethod that takes a number of bins (its current number of bins is ignored)
+/// * `strategy` - The AutoStrategy used to choose the number of bins
+/// * `max_bins` - The maximum number of bins to consider
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Edge Cases
+///
+/// * The chosen number of bins is never greater than max_bins or the number of unique values in the dataset
+/// * Scores are computed for every number of bins from 1 to that limit, regardless of the strategy
+///
+/// # Examples
+///
+/// ```
+/// use classify::{select_num_bins, AutoStrategy, Jenks};
+///
+/// let data: Vec<f64> = vec![1.0, 1.1, 1.2, 5.0, 5.1, 5.2, 9.0, 9.1, 9.2, 9.3];
+///
+/// let result = select_num_bins(Jenks { num_bins: 1 }.into(), AutoStrategy::GvfThreshold(0.95), 6, &data).unwrap();
+///
+/// assert_eq!(result.num_bins, 3);
+/// assert_eq!(result.method, Jenks { num_bins: 3 }.into());
+/// assert_eq!(result.scores.len(), 6);
+/// assert_eq!(result.scores[0], (1, 0.0));
+/// ```
+pub fn select_num_bins<T: ToPrimitive>(
+    method: ClassificationMethod,
+    strategy: AutoStrategy,
+    max_bins: usize,
+    data: &[T],
+) -> Result<AutoSelection, ClassifyError> {
+    if method.num_bins().is_none() {
+        return Err(ClassifyError::InvalidParameter(
+            "method must take a number of bins".to_string(),
+        ));
+    }
+    if max_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(max_bins));
+    }
+    let sorted = SortedData::try_new(data)?;
+    let true_max_bins = std::cmp::min(max_bins, sorted.unique_vals().len());
+
+    let bin_counts: Vec<usize> = (1..=true_max_bins).collect();
+    let scores = map_slice(&bin_counts, |&k| -> Result<(usize, f64), ClassifyError> {
+        let breaks = method.with_num_bins(k).unwrap().breaks_sorted(&sorted)?;
+        Ok((k, metrics::gvf_sorted(&breaks, &sorted)?))
+    })
+    .into_iter()
+    .collect::<Result<Vec<(usize, f64)>, ClassifyError>>()?;
+
+    let num_bins = match strategy {
+        AutoStrategy::GvfThreshold(threshold) => {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(ClassifyError::InvalidParameter(
+                    "GVF threshold must be between 0 and 1".to_string(),
+                ));
+            }
+            scores
+                .iter()
+                .find(|(_, gvf)| *gvf >= threshold)
+                .map_or(true_max_bins, |(k, _)| *k)
+        }
+        AutoStrategy::GvfElbow => calc_elbow(&scores),
+        _ => calc_rule_num_bins(strategy, sorted.values()).clamp(1, true_max_bins),
+    };
+
+    Ok(AutoSelection {
+        num_bins,
+        method: method.with_num_bins(num_bins).unwrap(),
+        scores,
+    })
+}
+
+/// Returns the number of bins at the point of a GVF curve furthest above the line joining its first and last points
+fn calc_elbow(scores: &[(usize, f64)]) -> usize {
+    let num_scores = scores.len();
+    if num_scores < 3 {
+        return num_scores;
+    }
+    let (first_k, first_gvf) = scores[0];
+    let (last_k, last_gvf) = scores[num_scores - 1];
+    let slope = (last_gvf - first_gvf) / (last_k - first_k) as f64;
+
+    let mut best_k = last_k;
+    let mut best_dist: f64 = 0.0;
+    for (k, gvf) in scores {
+        let dist = gvf - (first_gvf + slope * (k - first_k) as f64);
+        if dist > best_dist {
+            best_dist = dist;
+            best_k = *k;
+        }
+    }
+    best_k
+}
+
+/// Returns the (unclamped) number of bins suggested by a rule-based strategy for sorted data
+fn calc_rule_num_bins(strategy: AutoStrategy, sorted_data: &[f64]) -> usize {
+    let num_vals = sorted_data.len();
+    let n = num_vals as f64;
+    let range = sorted_data[num_vals - 1] - sorted_data[0];
+    if num_vals < 2 || range == 0.0 {
+        return 1;
+    }
+
+    let from_width = |width: f64| -> usize {
+        if width > 0.0 {
+            (range / width).ceil() as usize
+        } else {
+            1
+        }
+    };
+
+    match strategy {
+        AutoStrategy::Sturges => n.log2().ceil() as usize + 1,
+        AutoStrategy::Scott => from_width(3.49 * calc_st_dev(sorted_data) * n.powf(-1.0 / 3.0)),
+        AutoStrategy::FreedmanDiaconis => {
+            let iqr = percentile(75, sorted_data) - percentile(25, sorted_data);
+            from_width(2.0 * iqr * n.powf(-1.0 / 3.0))
+        }
+        AutoStrategy::Doane => {
+            let mean: f64 = sorted_data.iter().sum::<f64>() / n;
+            let mut m2: f64 = 0.0;
+            let mut m3: f64 = 0.0;
+            for item in sorted_data {
+                m2 += (item - mean).powi(2) / n;
+                m3 += (item - mean).powi(3) / n;
+            }
+            let skewness = m3 / m2.powf(1.5);
+            let skewness_error = (6.0 * (n - 2.0) / ((n + 1.0) * (n + 3.0))).sqrt();
+            let extra = if skewness_error > 0.0 {
+                (1.0 + skewness.abs() / skewness_error).log2()
+            } else {
+                0.0
+            };
+            (1.0 + n.log2() + extra).ceil() as usize
+        }
+        AutoStrategy::JenksCaspall => (5.0 * n.log10()).round() as usize,
+        AutoStrategy::GvfThreshold(_) | AutoStrategy::GvfElbow => 1,
+    }
+}
@@ -0,0 +1,356 @@
+use num_traits::ToPrimitive;
+
+use crate::classifier::{Ckmeans, Classifier};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
+use crate::utilities::{try_to_weighted_vals, unique_to_weighted_vals, PrefixSums};
+use crate::utilities::{Classification, WeightedVal};
+
+/// Represents the outcome of optimal one-dimensional k-means clustering (Ckmeans.1d.dp)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CkmeansClustering {
+    /// The breaks between clusters (the lowest value of every cluster but the first)
+    pub breaks: Vec<f64>,
+    /// The mean of every cluster, in ascending order
+    pub means: Vec<f64>,
+    /// The within-cluster sum of squared deviations of every cluster
+    pub withinss: Vec<f64>,
+}
+
+/// Returns a Classification object following the Ckmeans (optimal one-dimensional k-means) algorithm given the desired number of bins and one-dimensional data
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * Panics on empty data, non-finite data or zero bins; use try_get_ckmeans_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_ckmeans_classification;
+/// use classify::{Classification, Bin};
+///
+/// let data: Vec<f64> = vec![-1.0, 2.0, -1.0, 2.0, 4.0, 5.0, 6.0, -1.0, 2.0, -1.0];
+///
+/// let result: Classification = get_ckmeans_classification(3, &data);
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(-1.0, 2.0, 4),
+///     Bin::new(2.0, 4.0, 3),
+///     Bin::new(4.0, 6.0, 3)
+/// ];
+///
+/// assert!(result == expected);
+/// ```
+pub fn get_ckmeans_classification<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Classification {
+    try_get_ckmeans_classification(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Ckmeans (optimal one-dimensional k-means) algorithm given the desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_ckmeans_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0];
+///
+/// assert_eq!(try_get_ckmeans_classification(0, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_ckmeans_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    Ckmeans { num_bins }.classify(data)
+}
+
+/// Returns a Classification object following the Ckmeans (optimal one-dimensional k-means) algorithm given the desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_ckmeans_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    Ckmeans { num_bins }.classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Ckmeans (optimal one-dimensional k-means) algorithm given the desired number of bins and a dataset
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * Panics on empty data, non-finite data or zero bins; use try_get_ckmeans_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_ckmeans_breaks;
+///
+/// let data: Vec<i32> = vec![1, 2, 4, 5, 7, 8];
+///
+/// let result: Vec<f64> = get_ckmeans_breaks(3, &data);
+///
+/// assert_eq!(result, vec![4.0, 7.0]);
+/// ```
+pub fn get_ckmeans_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64> {
+    try_get_ckmeans_breaks(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Ckmeans (optimal one-dimensional k-means) algorithm given the desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_ckmeans_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(try_get_ckmeans_breaks(3, &data), Ok(vec![4.0, 7.0]));
+/// assert_eq!(try_get_ckmeans_breaks(3, &Vec::<f64>::new()), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_ckmeans_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    Ok(try_get_ckmeans_clustering(num_bins, data)?.breaks)
+}
+
+/// Returns a vector of breaks generated through the Ckmeans (optimal one-dimensional k-means) algorithm given the desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_ckmeans_breaks, try_get_ckmeans_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_ckmeans_breaks_with_policy(3, &data, &policy), try_get_ckmeans_breaks(3, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_ckmeans_breaks_with_policy(3, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_ckmeans_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    Ckmeans { num_bins }.breaks_with_policy(data, policy)
+}
+
+/// Returns the breaks, cluster means and within-cluster sums of squared deviations of the optimal one-dimensional k-means clustering of a dataset given the desired number of clusters, or a ClassifyError if the input is invalid
+///
+/// The clustering is computed through the dynamic programming approach of Ckmeans.1d.dp, filling each row of the DP matrix by divide and conquer in O(n log n) time
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of clusters
+/// * `data` - A reference to a collection of unsorted data points to cluster
+///
+/// # Edge Cases
+///
+/// * The number of clusters is limited to the number of unique values in the dataset
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_ckmeans_clustering;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 10.0, 11.0, 12.0];
+///
+/// let result = try_get_ckmeans_clustering(2, &data).unwrap();
+///
+/// assert_eq!(result.breaks, vec![10.0]);
+/// assert_eq!(result.means, vec![2.0, 11.0]);
+/// assert_eq!(result.withinss, vec![2.0, 2.0]);
+/// ```
+pub fn try_get_ckmeans_clustering<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<CkmeansClustering, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    try_get_ckmeans_clustering_sorted(num_bins, &SortedData::try_new_without_sums(data)?)
+}
+
+/// Returns the optimal one-dimensional k-means clustering of a SortedData object given the desired number of clusters, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_ckmeans_clustering_sorted(
+    num_bins: usize,
+    sorted: &SortedData,
+) -> Result<CkmeansClustering, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let sorted_data = sorted.values();
+    let unique_val_map = sorted.unique_vals();
+
+    let true_num_bins = std::cmp::min(unique_val_map.len(), num_bins);
+    let starts = calc_ckmeans_starts(&unique_to_weighted_vals(unique_val_map), true_num_bins);
+
+    let mut breaks: Vec<f64> = vec![];
+    let mut means: Vec<f64> = vec![];
+    let mut withinss: Vec<f64> = vec![];
+    for (i, start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).map_or(unique_val_map.len(), |next| *next);
+        let cluster = &sorted_data[unique_val_map[*start].first..unique_val_map[end - 1].last + 1];
+        let mean: f64 = cluster.iter().sum::<f64>() / cluster.len() as f64;
+        if i > 0 {
+            breaks.push(unique_val_map[*start].val);
+        }
+        means.push(mean);
+        withinss.push(
+            cluster
+                .iter()
+                .map(|item| (item - mean) * (item - mean))
+                .sum(),
+        );
+    }
+
+    Ok(CkmeansClustering {
+        breaks,
+        means,
+        withinss,
+    })
+}
+
+/// Returns a vector of breaks of the optimal one-dimensional k-means clustering of a weighted dataset given the desired number of clusters, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_ckmeans_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+
+    let true_num_bins = std::cmp::min(weighted_vals.len(), num_bins);
+    let starts = calc_ckmeans_starts(&weighted_vals, true_num_bins);
+
+    Ok(starts
+        .iter()
+        .skip(1)
+        .map(|start| weighted_vals[*start].val)
+        .collect())
+}
+
+/// Returns the index (into the weighted unique values) of the first unique value of every cluster of the optimal clustering into the given number of clusters
+///
+/// Each unique value is weighted by its total weight, and the data is shifted by its median to limit cancellation in the prefix sums
+///
+/// # Arguments
+///
+/// * `u_val_map` - A reference to a collection of sorted unique values along with their total weights
+/// * `num_bins` - The desired number of clusters (must be between 1 and the number of unique values)
+fn calc_ckmeans_starts(u_val_map: &[WeightedVal], num_bins: usize) -> Vec<usize> {
+    let num_unique_vals = u_val_map.len();
+    let prefix_sums = PrefixSums::from_weighted(u_val_map);
+    let ssd = |first: usize, last: usize| -> f64 { prefix_sums.ssd(first, last + 1) };
+
+    // costs[q][i] holds the minimum SSD of splitting unique values 0..=i into q + 1 clusters, and
+    // starts[q][i] holds the index of the first unique value in the last of those clusters
+    let mut costs: Vec<Vec<f64>> = vec![vec![0.0; num_unique_vals]; num_bins];
+    let mut starts: Vec<Vec<usize>> = vec![vec![0; num_unique_vals]; num_bins];
+    for (i, cost) in costs[0].iter_mut().enumerate() {
+        *cost = ssd(0, i);
+    }
+    for q in 1..num_bins {
+        let (previous, current) = costs.split_at_mut(q);
+        fill_row(
+            &previous[q - 1],
+            &mut current[0],
+            &mut starts[q],
+            &ssd,
+            (q, num_unique_vals - 1),
+            (q, num_unique_vals - 1),
+        );
+    }
+
+    let mut result: Vec<usize> = vec![0; num_bins];
+    let mut last = num_unique_vals - 1;
+    for q in (1..num_bins).rev() {
+        result[q] = starts[q][last];
+        last = starts[q][last] - 1;
+    }
+    result
+}
+
+/// Fills a row of the DP matrix between two indices by divide and conquer, relying on the optimal start of the last cluster never decreasing as more values are included
+fn fill_row(
+    previous_costs: &[f64],
+    costs: &mut [f64],
+    starts: &mut [usize],
+    ssd: &dyn Fn(usize, usize) -> f64,
+    (i_min, i_max): (usize, usize),
+    (j_min, j_max): (usize, usize),
+) {
+    if i_min > i_max {
+        return;
+    }
+    let i = (i_min + i_max) / 2;
+    let mut best_cost = f64::INFINITY;
+    let mut best_start = j_min;
+    for j in j_min..=std::cmp::min(i, j_max) {
+        let cost = previous_costs[j - 1] + ssd(j, i);
+        if cost < best_cost {
+            best_cost = cost;
+            best_start = j;
+        }
+    }
+    costs[i] = best_cost;
+    starts[i] = best_start;
+
+    if i > i_min {
+        fill_row(
+            previous_costs,
+            costs,
+            starts,
+            ssd,
+            (i_min, i - 1),
+            (j_min, best_start),
+        );
+    }
+    fill_row(
+        previous_costs,
+        costs,
+        starts,
+        ssd,
+        (i + 1, i_max),
+        (best_start, j_max),
+    );
+}
@@ -0,0 +1,1176 @@
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+use crate::diagnostics::{Event, Observer, Report};
+use crate::error::ClassifyError;
+use crate::jenks::{JenksCaspallStart, JenksOptions};
+use crate::missing::{apply_missing_policy, apply_missing_policy_weighted, MissingPolicy};
+use crate::percentiles::QuantileDefinition;
+use crate::sketch::QuantileSketch;
+use crate::sorted_data::SortedData;
+use crate::utilities::try_to_weights;
+use crate::utilities::{try_breaks_to_classification, try_breaks_to_classification_with_closure};
+use crate::utilities::{try_breaks_to_classification_weighted, try_to_weighted_vals};
+use crate::utilities::{try_histogram_to_classification, try_split_histogram};
+use crate::utilities::{Classification, Closure};
+
+/// Common interface implemented by the configuration of every classification algorithm
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, Jenks, Quantile};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(Jenks { num_bins: 3 }.breaks(&data), Ok(vec![4.0, 7.0]));
+/// assert_eq!(Quantile { num_bins: 2, definition: Default::default() }.breaks(&data), Ok(vec![4.5]));
+/// ```
+pub trait Classifier {
+    /// Returns a vector of breaks for the given dataset, or a ClassifyError if the input is invalid
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError>;
+
+    /// Returns a vector of breaks for the given dataset while emitting diagnostic events to an Observer (algorithms without diagnostics emit no events)
+    fn breaks_observed<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        _observer: &mut dyn Observer,
+    ) -> Result<Vec<f64>, ClassifyError> {
+        self.breaks(data)
+    }
+
+    /// Returns a vector of breaks for the given dataset along with a Report summarizing the diagnostics collected while generating them
+    ///
+    /// The elapsed time is left at zero on wasm32-unknown-unknown, which has no clock
+    fn breaks_with_report<T: ToPrimitive>(
+        &self,
+        data: &[T],
+    ) -> Result<(Vec<f64>, Report), ClassifyError> {
+        let mut report = Report::default();
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        let start = Instant::now();
+        let breaks: Vec<f64> = self.breaks_observed(data, &mut report)?;
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        {
+            report.elapsed = start.elapsed();
+        }
+        Ok((breaks, report))
+    }
+
+    /// Returns a Classification object for the given dataset, recording this classifier as its method, or a ClassifyError if the input is invalid
+    fn classify<T: ToPrimitive>(&self, data: &[T]) -> Result<Classification, ClassifyError> {
+        let breaks: Vec<f64> = self.breaks(data)?;
+        let mut classification = try_breaks_to_classification(&breaks, data)?;
+        classification.method = Some(self.method());
+        Ok(classification)
+    }
+
+    /// Returns a Classification object for the given dataset whose bins have the given closure, recording this classifier as its method, or a ClassifyError if the input is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Classifier, Closure, EqualInterval};
+    ///
+    /// let data: Vec<f64> = vec![0.0, 1.0, 1.5, 2.0, 3.0];
+    /// let method = EqualInterval { num_bins: 3 };
+    ///
+    /// let left = method.classify(&data).unwrap();
+    /// let right = method.classify_with_closure(&data, Closure::RightClosed).unwrap();
+    ///
+    /// assert_eq!(left.iter().map(|bin| bin.count).collect::<Vec<u64>>(), vec![1, 2, 2]);
+    /// assert_eq!(right.iter().map(|bin| bin.count).collect::<Vec<u64>>(), vec![2, 2, 1]);
+    /// ```
+    fn classify_with_closure<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        closure: Closure,
+    ) -> Result<Classification, ClassifyError> {
+        let breaks: Vec<f64> = self.breaks(data)?;
+        let mut classification = try_breaks_to_classification_with_closure(&breaks, data, closure)?;
+        classification.method = Some(self.method());
+        Ok(classification)
+    }
+
+    /// Returns a vector of breaks for a dataset that has already been sorted into a SortedData object, or a ClassifyError if the input is invalid
+    ///
+    /// Running several classifiers on the same SortedData sorts the data only once; the breaks are the same as those of Classifier::breaks on the original data
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        self.breaks(data.values())
+    }
+
+    /// Returns a Classification object for a dataset that has already been sorted into a SortedData object, recording this classifier as its method, or a ClassifyError if the input is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Classifier, ClassificationMethod, HeadTail, Jenks, SortedData, StdDev};
+    ///
+    /// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0, 30.0];
+    /// let sorted = SortedData::new(&data);
+    ///
+    /// let methods: Vec<ClassificationMethod> = vec![
+    ///     Jenks { num_bins: 3 }.into(),
+    ///     HeadTail.into(),
+    ///     StdDev { bin_size: 1.0 }.into(),
+    /// ];
+    /// for method in &methods {
+    ///     let result = method.classify_sorted(&sorted).unwrap();
+    ///     assert_eq!(result.breaks(), method.classify(&data).unwrap().breaks());
+    ///     assert_eq!(result.num_vals, 7);
+    /// }
+    /// ```
+    fn classify_sorted(&self, data: &SortedData) -> Result<Classification, ClassifyError> {
+        let breaks: Vec<f64> = self.breaks_sorted(data)?;
+        let mut classification = try_breaks_to_classification(&breaks, data.values())?;
+        classification.method = Some(self.method());
+        Ok(classification)
+    }
+
+    /// Returns a vector of breaks for the given dataset where each data point carries a weight (e.g. a population or an area), or a ClassifyError if the input is invalid
+    ///
+    /// Weights act as frequencies, so an integer weight gives the same breaks as repeating the data point that many times, except for Standard Deviation Breaks, which treats them as reliability weights so that only their relative sizes matter; algorithms that only depend on the range of the data (or on user-given edges) ignore the weights other than leaving out points whose weight is zero, like every other algorithm
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Classifier, EqualInterval};
+    ///
+    /// let data: Vec<f64> = vec![0.0, 1.0, 2.0, 3.0, 100.0];
+    /// let weights: Vec<f64> = vec![1.0, 1.0, 1.0, 1.0, 0.0];
+    ///
+    /// assert_eq!(EqualInterval { num_bins: 3 }.breaks_weighted(&data, &weights), Ok(vec![1.0, 2.0]));
+    /// ```
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        let vals: Vec<f64> = try_to_weighted_vals(data, weights)?
+            .iter()
+            .map(|item| item.val)
+            .collect();
+        self.breaks(&vals)
+    }
+
+    /// Returns a Classification object for the given dataset where each data point carries a weight, recording this classifier as its method, or a ClassifyError if the input is invalid
+    ///
+    /// The bins' weight_sum fields hold the total weight of their points and the fit statistics are weighted accordingly
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Classifier, Quantile};
+    ///
+    /// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
+    /// let weights: Vec<f64> = vec![1.0, 1.0, 1.0, 5.0];
+    /// let method = Quantile { num_bins: 2, definition: Default::default() };
+    ///
+    /// let result = method.classify_weighted(&data, &weights).unwrap();
+    ///
+    /// assert_eq!(result.breaks(), vec![4.0]);
+    /// assert_eq!(result.iter().map(|bin| bin.weight_sum).collect::<Vec<f64>>(), vec![3.0, 5.0]);
+    /// ```
+    fn classify_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Classification, ClassifyError> {
+        let breaks: Vec<f64> = self.breaks_weighted(data, weights)?;
+        let mut classification = try_breaks_to_classification_weighted(&breaks, data, weights)?;
+        classification.method = Some(self.method());
+        Ok(classification)
+    }
+
+    /// Returns a vector of breaks for a histogram of (value, count) pairs (e.g. a summarized raster), treating each value as occurring count times without expanding the data, or a ClassifyError if the input is invalid
+    fn breaks_from_histogram(&self, histogram: &[(f64, u64)]) -> Result<Vec<f64>, ClassifyError> {
+        let (vals, counts) = try_split_histogram(histogram)?;
+        self.breaks_weighted(&vals, &counts)
+    }
+
+    /// Returns a Classification object for a histogram of (value, count) pairs, whose bins count the occurrences within them, recording this classifier as its method, or a ClassifyError if the input is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Classifier, Jenks};
+    ///
+    /// let histogram: Vec<(f64, u64)> = vec![(1.0, 5000), (2.0, 4000), (10.0, 300), (11.0, 200)];
+    ///
+    /// let result = Jenks { num_bins: 2 }.classify_from_histogram(&histogram).unwrap();
+    ///
+    /// assert_eq!(result.breaks(), vec![10.0]);
+    /// assert_eq!(result.iter().map(|bin| bin.count).collect::<Vec<u64>>(), vec![9000, 500]);
+    /// ```
+    fn classify_from_histogram(
+        &self,
+        histogram: &[(f64, u64)],
+    ) -> Result<Classification, ClassifyError> {
+        let breaks: Vec<f64> = self.breaks_from_histogram(histogram)?;
+        let mut classification = try_histogram_to_classification(&breaks, histogram)?;
+        classification.method = Some(self.method());
+        Ok(classification)
+    }
+
+    /// Returns a vector of approximate breaks for a dataset summarized by a QuantileSketch, treating its retained samples as weighted data, or a ClassifyError if the sketch is empty or the input is otherwise invalid
+    ///
+    /// Breaks placed at ranks (Quantile, Percentiles, Hinge) are within the sketch's rank error of the exact ones; other methods see only the samples, whose lowest and highest values are the exact minimum and maximum
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Classifier, HeadTail, Hinge, QuantileSketch};
+    ///
+    /// let data: Vec<f64> = (1..=20000).map(|i| 1000.0 / i as f64).collect();
+    /// let mut sketch = QuantileSketch::default();
+    /// for chunk in data.chunks(5000) {
+    ///     sketch.try_extend(chunk).unwrap();
+    /// }
+    ///
+    /// let method = Hinge { hinge_coefficient: 1.5 };
+    /// assert_eq!(method.breaks_from_sketch(&sketch).unwrap().len(), method.breaks(&data).unwrap().len());
+    /// assert!(!HeadTail.breaks_from_sketch(&sketch).unwrap().is_empty());
+    /// assert!(HeadTail.breaks_from_sketch(&QuantileSketch::default()).is_err());
+    /// ```
+    fn breaks_from_sketch(&self, sketch: &QuantileSketch) -> Result<Vec<f64>, ClassifyError> {
+        let (vals, weights) = sketch.try_to_weighted()?;
+        self.breaks_weighted(&vals, &weights)
+    }
+
+    /// Returns a vector of breaks for the given dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+    fn breaks_with_policy<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        policy: &MissingPolicy,
+    ) -> Result<Vec<f64>, ClassifyError> {
+        let (values, _) = apply_missing_policy(data, policy)?;
+        self.breaks(&values)
+    }
+
+    /// Returns a Classification object for the given dataset, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Classifier, EqualInterval, MissingPolicy};
+    ///
+    /// let data: Vec<f64> = vec![0.0, f64::NAN, 1.0, 2.0, -9999.0, 3.0];
+    /// let method = EqualInterval { num_bins: 3 };
+    ///
+    /// let result = method.classify_with_policy(&data, &MissingPolicy::Sentinels(vec![-9999.0])).unwrap();
+    ///
+    /// assert_eq!(result.breaks(), vec![1.0, 2.0]);
+    /// assert_eq!(result.num_vals, 4);
+    /// assert_eq!(result.num_skipped, 2);
+    /// assert!(method.classify_with_policy(&data, &MissingPolicy::Error).is_err());
+    /// ```
+    fn classify_with_policy<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        policy: &MissingPolicy,
+    ) -> Result<Classification, ClassifyError> {
+        let (values, num_skipped) = apply_missing_policy(data, policy)?;
+        let mut classification = self.classify(&values)?;
+        classification.set_missing(num_skipped, num_skipped as f64, policy);
+        Ok(classification)
+    }
+
+    /// Returns a vector of breaks for the given dataset where each data point carries a weight, handling missing values according to a MissingPolicy (dropping the weights of skipped values), or a ClassifyError if the input is invalid
+    fn breaks_weighted_with_policy<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+        policy: &MissingPolicy,
+    ) -> Result<Vec<f64>, ClassifyError> {
+        let (values, weights, _) = apply_missing_policy_weighted(data, weights, policy)?;
+        self.breaks_weighted(&values, &weights)
+    }
+
+    /// Returns a Classification object for the given dataset where each data point carries a weight, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Classifier, MissingPolicy, Quantile};
+    ///
+    /// let data: Vec<f64> = vec![1.0, 2.0, -9999.0, 3.0, 4.0];
+    /// let weights: Vec<f64> = vec![1.0, 1.0, 8.0, 1.0, 5.0];
+    /// let method = Quantile { num_bins: 2, definition: Default::default() };
+    ///
+    /// let result = method
+    ///     .classify_weighted_with_policy(&data, &weights, &MissingPolicy::NoDataBin(vec![-9999.0]))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(result.breaks(), vec![4.0]);
+    /// assert_eq!(result.no_data_count, Some(1));
+    /// ```
+    fn classify_weighted_with_policy<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+        policy: &MissingPolicy,
+    ) -> Result<Classification, ClassifyError> {
+        let (values, kept_weights, num_skipped) =
+            apply_missing_policy_weighted(data, weights, policy)?;
+        let mut classification = self.classify_weighted(&values, &kept_weights)?;
+        let skipped_weight: f64 = data
+            .iter()
+            .zip(try_to_weights(weights, data.len())?)
+            .filter(|(item, _)| policy.is_missing(item.to_f64()))
+            .map(|(_, weight)| weight)
+            .sum();
+        classification.set_missing(num_skipped, skipped_weight, policy);
+        Ok(classification)
+    }
+
+    /// Returns the ClassificationMethod describing this classifier and its parameters
+    fn method(&self) -> ClassificationMethod;
+}
+
+/// Configuration for the (exact) Jenks Natural Breaks algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Jenks {
+    pub num_bins: usize,
+}
+
+impl Classifier for Jenks {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_jenks_breaks(self.num_bins, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_jenks_breaks_sorted(self.num_bins, data, &mut |_: &Event| {})
+    }
+
+    fn breaks_observed<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        observer: &mut dyn Observer,
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_jenks_breaks_observed(self.num_bins, data, observer)
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_jenks_breaks_weighted(self.num_bins, data, weights)
+    }
+}
+
+/// Configuration for the approximate (random search) Jenks Natural Breaks algorithm, where `options` are the JenksOptions of the search (JenksOptions::default() by default)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ApproxJenks {
+    pub num_bins: usize,
+    #[serde(default)]
+    pub options: JenksOptions,
+}
+
+impl Classifier for ApproxJenks {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        self.breaks_observed(data, &mut |_: &Event| {})
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_approx_jenks_breaks_sorted(
+            self.num_bins,
+            &self.options,
+            data,
+            &mut |_: &Event| {},
+        )
+    }
+
+    fn breaks_observed<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        observer: &mut dyn Observer,
+    ) -> Result<Vec<f64>, ClassifyError> {
+        if self.num_bins == 0 {
+            return Err(ClassifyError::InvalidBinCount(self.num_bins));
+        }
+        crate::jenks::try_get_approx_jenks_breaks_sorted(
+            self.num_bins,
+            &self.options,
+            &SortedData::try_new_without_sums(data)?,
+            observer,
+        )
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_approx_jenks_breaks_weighted(
+            self.num_bins,
+            &self.options,
+            data,
+            weights,
+        )
+    }
+}
+
+/// Configuration for the Jenks-Caspall algorithm, which refines initial breaks chosen by `start` by moving observations between neighbouring classes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JenksCaspall {
+    pub num_bins: usize,
+    pub start: JenksCaspallStart,
+}
+
+impl Classifier for JenksCaspall {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_jenks_caspall_breaks(self.num_bins, self.start, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        Ok(crate::jenks::try_get_jenks_caspall_refinement_sorted(
+            self.num_bins,
+            self.start,
+            data,
+            &mut |_: &Event| {},
+        )?
+        .breaks)
+    }
+
+    fn breaks_observed<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        observer: &mut dyn Observer,
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_jenks_caspall_breaks_observed(
+            self.num_bins,
+            self.start,
+            data,
+            observer,
+        )
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::jenks::try_get_jenks_caspall_breaks_weighted(
+            self.num_bins,
+            self.start,
+            data,
+            weights,
+        )
+    }
+}
+
+/// Configuration for the Ckmeans (optimal one-dimensional k-means) algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ckmeans {
+    pub num_bins: usize,
+}
+
+impl Classifier for Ckmeans {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::ckmeans::try_get_ckmeans_breaks(self.num_bins, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        Ok(crate::ckmeans::try_get_ckmeans_clustering_sorted(self.num_bins, data)?.breaks)
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::ckmeans::try_get_ckmeans_breaks_weighted(self.num_bins, data, weights)
+    }
+}
+
+/// Configuration for the Maximum Breaks algorithm, where `min_class_size` is the minimum number of data points in every bin (0 and 1 both mean no constraint)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MaximumBreaks {
+    pub num_bins: usize,
+    pub min_class_size: usize,
+}
+
+impl Classifier for MaximumBreaks {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::maximum_breaks::try_get_maximum_breaks_with_min_class_size(
+            self.num_bins,
+            self.min_class_size,
+            data,
+        )
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::maximum_breaks::try_get_maximum_breaks_sorted(
+            self.num_bins,
+            self.min_class_size,
+            data,
+        )
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::maximum_breaks::try_get_maximum_breaks_weighted(
+            self.num_bins,
+            self.min_class_size,
+            data,
+            weights,
+        )
+    }
+}
+
+/// Configuration for the Geometric Interval algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeometricInterval {
+    pub num_bins: usize,
+}
+
+impl Classifier for GeometricInterval {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::geometric_interval::try_get_geometric_interval_breaks(self.num_bins, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::geometric_interval::try_get_geometric_interval_breaks_sorted(self.num_bins, data)
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::geometric_interval::try_get_geometric_interval_breaks_weighted(
+            self.num_bins,
+            data,
+            weights,
+        )
+    }
+}
+
+/// Configuration for the Pretty Breaks algorithm, where `num_bins` is the approximate desired number of bins
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pretty {
+    pub num_bins: usize,
+}
+
+impl Classifier for Pretty {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::pretty::try_get_pretty_breaks(self.num_bins, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::pretty::try_get_pretty_breaks_sorted(self.num_bins, data)
+    }
+}
+
+/// Configuration for the Quantile Breaks algorithm, where `definition` is the definition of the sample quantile used to place the breaks (QuantileDefinition::Midpoint by default)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quantile {
+    pub num_bins: usize,
+    #[serde(default)]
+    pub definition: QuantileDefinition,
+}
+
+impl Classifier for Quantile {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::quantile::try_get_quantile_breaks_with_definition(
+            self.num_bins,
+            self.definition,
+            data,
+        )
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::quantile::try_get_quantile_breaks_sorted(self.num_bins, self.definition, data)
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::quantile::try_get_quantile_breaks_weighted(
+            self.num_bins,
+            self.definition,
+            data,
+            weights,
+        )
+    }
+}
+
+/// Configuration for Percentile Breaks, where `percentiles` are the percentiles (between 0 and 100) at which to place breaks and `definition` is the definition of the sample quantile used to calculate them
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, Percentiles, QuantileDefinition};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+/// let method = Percentiles { percentiles: vec![50.0, 90.0], definition: QuantileDefinition::Type1 };
+///
+/// assert_eq!(method.breaks(&data), Ok(vec![5.0, 9.0]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub percentiles: Vec<f64>,
+    pub definition: QuantileDefinition,
+}
+
+impl Classifier for Percentiles {
+    fn method(&self) -> ClassificationMethod {
+        self.clone().into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::percentiles::calc_percentile_breaks(self, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::percentiles::calc_percentile_breaks_sorted(self, data)
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::percentiles::calc_percentile_breaks_weighted(self, data, weights)
+    }
+}
+
+/// Configuration for the Head-Tail Breaks algorithm, which takes no parameters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeadTail;
+
+impl Classifier for HeadTail {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::head_tail::try_get_head_tail_breaks(data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::head_tail::try_get_head_tail_breaks_sorted(data, &mut |_: &Event| {})
+    }
+
+    fn breaks_observed<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        observer: &mut dyn Observer,
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::head_tail::try_get_head_tail_breaks_observed(data, observer)
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::head_tail::try_get_head_tail_breaks_weighted(data, weights)
+    }
+}
+
+/// Configuration for the Equal Interval Breaks algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EqualInterval {
+    pub num_bins: usize,
+}
+
+impl Classifier for EqualInterval {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::equal_interval::try_get_equal_interval_breaks(self.num_bins, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::equal_interval::try_get_equal_interval_breaks_sorted(self.num_bins, data)
+    }
+}
+
+/// Configuration for the Defined Interval algorithm, where `width` is the width of each bin, `anchor` is a value every break is offset from by a multiple of the width (None anchors the bins at the minimum of the data), and `max_classes` optionally limits the number of bins the width may produce
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, DefinedInterval};
+///
+/// let data: Vec<f64> = vec![-12.0, 3.0, 18.0, 41.0];
+/// let method = DefinedInterval { width: 25.0, anchor: Some(0.0), max_classes: Some(5) };
+///
+/// assert_eq!(method.breaks(&data), Ok(vec![0.0, 25.0]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DefinedInterval {
+    pub width: f64,
+    pub anchor: Option<f64>,
+    pub max_classes: Option<usize>,
+}
+
+impl Classifier for DefinedInterval {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::defined_interval::calc_defined_interval_breaks(self, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::defined_interval::calc_defined_interval_breaks_sorted(self, data)
+    }
+}
+
+/// Configuration for the Standard Deviation Breaks algorithm, where `bin_size` is the proportion of a standard deviation each bin should encompass
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StdDev {
+    pub bin_size: f64,
+}
+
+impl Classifier for StdDev {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::standard_deviation::try_get_st_dev_breaks(self.bin_size, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::standard_deviation::try_get_st_dev_breaks_sorted(self.bin_size, data)
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::standard_deviation::try_get_st_dev_breaks_weighted(self.bin_size, data, weights)
+    }
+
+    fn breaks_from_histogram(&self, histogram: &[(f64, u64)]) -> Result<Vec<f64>, ClassifyError> {
+        crate::standard_deviation::try_get_st_dev_breaks_from_histogram(self.bin_size, histogram)
+    }
+}
+
+/// Configuration for the Hinge Breaks algorithm, where `hinge_coefficient` is the size of the hinge as a multiple of the data's IQR
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hinge {
+    pub hinge_coefficient: f64,
+}
+
+impl Classifier for Hinge {
+    fn method(&self) -> ClassificationMethod {
+        (*self).into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::hinge::try_get_hinge_breaks(self.hinge_coefficient, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::hinge::try_get_hinge_breaks_sorted(self.hinge_coefficient, data)
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        crate::hinge::try_get_hinge_breaks_weighted(self.hinge_coefficient, data, weights)
+    }
+}
+
+/// Configuration for User-Defined Breaks, where `edges` are the edges of the classes (e.g. [0, 10, 25] for the classes 0-10 and 10-25), and `open_lower` and `open_upper` add open-ended classes below the first edge and above the last edge
+///
+/// Use UserDefined::new or parse a spec string (e.g. "<0, 10, 25, 50, >100") to validate and normalize the edges
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, UserDefined};
+///
+/// let data: Vec<f64> = vec![-5.0, 3.0, 12.0, 18.0, 30.0];
+/// let method: UserDefined = "<0, 10, 20, 30".parse().unwrap();
+///
+/// assert_eq!(method, UserDefined { edges: vec![0.0, 10.0, 20.0, 30.0], open_lower: true, open_upper: false });
+/// assert_eq!(method.breaks(&data), Ok(vec![0.0, 10.0, 20.0]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserDefined {
+    pub edges: Vec<f64>,
+    pub open_lower: bool,
+    pub open_upper: bool,
+}
+
+impl Classifier for UserDefined {
+    fn method(&self) -> ClassificationMethod {
+        self.clone().into()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        crate::user_defined::calc_user_defined_breaks(self, data)
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        crate::user_defined::calc_user_defined_breaks_sorted(self, data)
+    }
+}
+
+/// Represents a choice of classification algorithm along with its parameters, which can be stored (e.g. serialized) and replayed later
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, ClassificationMethod, Jenks};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+/// let method = ClassificationMethod::Jenks(Jenks { num_bins: 3 });
+///
+/// let json = serde_json::to_string(&method).unwrap();
+/// let restored: ClassificationMethod = serde_json::from_str(&json).unwrap();
+///
+/// assert_eq!(json, r#"{"Jenks":{"num_bins":3}}"#);
+/// assert_eq!(restored.breaks(&data), Ok(vec![4.0, 7.0]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClassificationMethod {
+    Jenks(Jenks),
+    ApproxJenks(ApproxJenks),
+    JenksCaspall(JenksCaspall),
+    Ckmeans(Ckmeans),
+    MaximumBreaks(MaximumBreaks),
+    GeometricInterval(GeometricInterval),
+    Pretty(Pretty),
+    Quantile(Quantile),
+    Percentiles(Percentiles),
+    HeadTail(HeadTail),
+    EqualInterval(EqualInterval),
+    DefinedInterval(DefinedInterval),
+    StdDev(StdDev),
+    Hinge(Hinge),
+    UserDefined(UserDefined),
+}
+
+impl ClassificationMethod {
+    /// Returns the number of bins requested by this method, or None if the method doesn't take a number of bins
+    pub fn num_bins(&self) -> Option<usize> {
+        match self {
+            ClassificationMethod::Jenks(method) => Some(method.num_bins),
+            ClassificationMethod::ApproxJenks(method) => Some(method.num_bins),
+            ClassificationMethod::JenksCaspall(method) => Some(method.num_bins),
+            ClassificationMethod::Ckmeans(method) => Some(method.num_bins),
+            ClassificationMethod::MaximumBreaks(method) => Some(method.num_bins),
+            ClassificationMethod::GeometricInterval(method) => Some(method.num_bins),
+            ClassificationMethod::Pretty(method) => Some(method.num_bins),
+            ClassificationMethod::Quantile(method) => Some(method.num_bins),
+            ClassificationMethod::EqualInterval(method) => Some(method.num_bins),
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of this method requesting the given number of bins (keeping its other parameters), or None if the method doesn't take a number of bins
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{ClassificationMethod, HeadTail, Quantile};
+    ///
+    /// let method = ClassificationMethod::Quantile(Quantile { num_bins: 3, definition: Default::default() });
+    ///
+    /// assert_eq!(method.with_num_bins(5), Some(Quantile { num_bins: 5, definition: Default::default() }.into()));
+    /// assert_eq!(ClassificationMethod::HeadTail(HeadTail).with_num_bins(5), None);
+    /// ```
+    pub fn with_num_bins(&self, num_bins: usize) -> Option<ClassificationMethod> {
+        match self {
+            ClassificationMethod::Jenks(_) => Some(Jenks { num_bins }.into()),
+            ClassificationMethod::ApproxJenks(method) => Some(
+                ApproxJenks {
+                    num_bins,
+                    options: method.options,
+                }
+                .into(),
+            ),
+            ClassificationMethod::JenksCaspall(method) => Some(
+                JenksCaspall {
+                    num_bins,
+                    start: method.start,
+                }
+                .into(),
+            ),
+            ClassificationMethod::Ckmeans(_) => Some(Ckmeans { num_bins }.into()),
+            ClassificationMethod::MaximumBreaks(method) => Some(
+                MaximumBreaks {
+                    num_bins,
+                    min_class_size: method.min_class_size,
+                }
+                .into(),
+            ),
+            ClassificationMethod::GeometricInterval(_) => {
+                Some(GeometricInterval { num_bins }.into())
+            }
+            ClassificationMethod::Pretty(_) => Some(Pretty { num_bins }.into()),
+            ClassificationMethod::Quantile(method) => Some(
+                Quantile {
+                    num_bins,
+                    definition: method.definition,
+                }
+                .into(),
+            ),
+            ClassificationMethod::EqualInterval(_) => Some(EqualInterval { num_bins }.into()),
+            _ => None,
+        }
+    }
+}
+
+impl Classifier for ClassificationMethod {
+    fn method(&self) -> ClassificationMethod {
+        self.clone()
+    }
+
+    fn breaks<T: ToPrimitive>(&self, data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+        match self {
+            ClassificationMethod::Jenks(method) => method.breaks(data),
+            ClassificationMethod::ApproxJenks(method) => method.breaks(data),
+            ClassificationMethod::JenksCaspall(method) => method.breaks(data),
+            ClassificationMethod::Ckmeans(method) => method.breaks(data),
+            ClassificationMethod::MaximumBreaks(method) => method.breaks(data),
+            ClassificationMethod::GeometricInterval(method) => method.breaks(data),
+            ClassificationMethod::Pretty(method) => method.breaks(data),
+            ClassificationMethod::Quantile(method) => method.breaks(data),
+            ClassificationMethod::Percentiles(method) => method.breaks(data),
+            ClassificationMethod::HeadTail(method) => method.breaks(data),
+            ClassificationMethod::EqualInterval(method) => method.breaks(data),
+            ClassificationMethod::DefinedInterval(method) => method.breaks(data),
+            ClassificationMethod::StdDev(method) => method.breaks(data),
+            ClassificationMethod::Hinge(method) => method.breaks(data),
+            ClassificationMethod::UserDefined(method) => method.breaks(data),
+        }
+    }
+
+    fn breaks_sorted(&self, data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+        match self {
+            ClassificationMethod::Jenks(method) => method.breaks_sorted(data),
+            ClassificationMethod::ApproxJenks(method) => method.breaks_sorted(data),
+            ClassificationMethod::JenksCaspall(method) => method.breaks_sorted(data),
+            ClassificationMethod::Ckmeans(method) => method.breaks_sorted(data),
+            ClassificationMethod::MaximumBreaks(method) => method.breaks_sorted(data),
+            ClassificationMethod::GeometricInterval(method) => method.breaks_sorted(data),
+            ClassificationMethod::Pretty(method) => method.breaks_sorted(data),
+            ClassificationMethod::Quantile(method) => method.breaks_sorted(data),
+            ClassificationMethod::Percentiles(method) => method.breaks_sorted(data),
+            ClassificationMethod::HeadTail(method) => method.breaks_sorted(data),
+            ClassificationMethod::EqualInterval(method) => method.breaks_sorted(data),
+            ClassificationMethod::DefinedInterval(method) => method.breaks_sorted(data),
+            ClassificationMethod::StdDev(method) => method.breaks_sorted(data),
+            ClassificationMethod::Hinge(method) => method.breaks_sorted(data),
+            ClassificationMethod::UserDefined(method) => method.breaks_sorted(data),
+        }
+    }
+
+    fn breaks_observed<T: ToPrimitive>(
+        &self,
+        data: &[T],
+        observer: &mut dyn Observer,
+    ) -> Result<Vec<f64>, ClassifyError> {
+        match self {
+            ClassificationMethod::Jenks(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::ApproxJenks(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::JenksCaspall(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::Ckmeans(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::MaximumBreaks(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::GeometricInterval(method) => {
+                method.breaks_observed(data, observer)
+            }
+            ClassificationMethod::Pretty(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::Quantile(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::Percentiles(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::HeadTail(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::EqualInterval(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::DefinedInterval(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::StdDev(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::Hinge(method) => method.breaks_observed(data, observer),
+            ClassificationMethod::UserDefined(method) => method.breaks_observed(data, observer),
+        }
+    }
+
+    fn breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+        &self,
+        data: &[T],
+        weights: &[W],
+    ) -> Result<Vec<f64>, ClassifyError> {
+        match self {
+            ClassificationMethod::Jenks(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::ApproxJenks(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::JenksCaspall(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::Ckmeans(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::MaximumBreaks(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::GeometricInterval(method) => {
+                method.breaks_weighted(data, weights)
+            }
+            ClassificationMethod::Pretty(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::Quantile(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::Percentiles(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::HeadTail(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::EqualInterval(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::DefinedInterval(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::StdDev(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::Hinge(method) => method.breaks_weighted(data, weights),
+            ClassificationMethod::UserDefined(method) => method.breaks_weighted(data, weights),
+        }
+    }
+    fn breaks_from_histogram(&self, histogram: &[(f64, u64)]) -> Result<Vec<f64>, ClassifyError> {
+        match self {
+            ClassificationMethod::Jenks(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::ApproxJenks(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::JenksCaspall(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::Ckmeans(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::MaximumBreaks(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::GeometricInterval(method) => {
+                method.breaks_from_histogram(histogram)
+            }
+            ClassificationMethod::Pretty(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::Quantile(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::Percentiles(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::HeadTail(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::EqualInterval(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::DefinedInterval(method) => {
+                method.breaks_from_histogram(histogram)
+            }
+            ClassificationMethod::StdDev(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::Hinge(method) => method.breaks_from_histogram(histogram),
+            ClassificationMethod::UserDefined(method) => method.breaks_from_histogram(histogram),
+        }
+    }
+}
+
+impl From<Jenks> for ClassificationMethod {
+    fn from(method: Jenks) -> Self {
+        ClassificationMethod::Jenks(method)
+    }
+}
+
+impl From<ApproxJenks> for ClassificationMethod {
+    fn from(method: ApproxJenks) -> Self {
+        ClassificationMethod::ApproxJenks(method)
+    }
+}
+
+impl From<JenksCaspall> for ClassificationMethod {
+    fn from(method: JenksCaspall) -> Self {
+        ClassificationMethod::JenksCaspall(method)
+    }
+}
+
+impl From<Ckmeans> for ClassificationMethod {
+    fn from(method: Ckmeans) -> Self {
+        ClassificationMethod::Ckmeans(method)
+    }
+}
+
+impl From<MaximumBreaks> for ClassificationMethod {
+    fn from(method: MaximumBreaks) -> Self {
+        ClassificationMethod::MaximumBreaks(method)
+    }
+}
+
+impl From<GeometricInterval> for ClassificationMethod {
+    fn from(method: GeometricInterval) -> Self {
+        ClassificationMethod::GeometricInterval(method)
+    }
+}
+
+impl From<Pretty> for ClassificationMethod {
+    fn from(method: Pretty) -> Self {
+        ClassificationMethod::Pretty(method)
+    }
+}
+
+impl From<Quantile> for ClassificationMethod {
+    fn from(method: Quantile) -> Self {
+        ClassificationMethod::Quantile(method)
+    }
+}
+
+impl From<Percentiles> for ClassificationMethod {
+    fn from(method: Percentiles) -> Self {
+        ClassificationMethod::Percentiles(method)
+    }
+}
+
+impl From<HeadTail> for ClassificationMethod {
+    fn from(method: HeadTail) -> Self {
+        ClassificationMethod::HeadTail(method)
+    }
+}
+
+impl From<EqualInterval> for ClassificationMethod {
+    fn from(method: EqualInterval) -> Self {
+        ClassificationMethod::EqualInterval(method)
+    }
+}
+
+impl From<DefinedInterval> for ClassificationMethod {
+    fn from(method: DefinedInterval) -> Self {
+        ClassificationMethod::DefinedInterval(method)
+    }
+}
+
+impl From<StdDev> for ClassificationMethod {
+    fn from(method: StdDev) -> Self {
+        ClassificationMethod::StdDev(method)
+    }
+}
+
+impl From<Hinge> for ClassificationMethod {
+    fn from(method: Hinge) -> Self {
+        ClassificationMethod::Hinge(method)
+    }
+}
+
+impl From<UserDefined> for ClassificationMethod {
+    fn from(method: UserDefined) -> Self {
+        ClassificationMethod::UserDefined(method)
+    }
+}
@@ -0,0 +1,329 @@
+use num_traits::ToPrimitive;
+
+use crate::classifier::{Classifier, DefinedInterval};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
+use crate::utilities::try_to_vec_f64;
+use crate::utilities::{try_param_to_f64, Classification};
+
+/// Returns a Classification object following the Defined Interval algorithm given the width of each bin and one-dimensional data
+///
+/// # Arguments
+///
+/// * `width` - A float representing the width of each bin (e.g. 10000 for bins of 10,000 people)
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The bins are anchored at the minimum of the data; use the DefinedInterval classifier to anchor them elsewhere
+/// * Panics on empty data, non-finite data or a width that isn't positive; use try_get_defined_interval_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_defined_interval_classification;
+/// use classify::{Classification, Bin};
+///
+/// let data: Vec<f64> = vec![2.0, 5.0, 9.0, 13.0, 27.0];
+///
+/// let result: Classification = get_defined_interval_classification(10.0, &data);
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(2.0, 12.0, 3),
+///     Bin::new(12.0, 22.0, 1),
+///     Bin::new(22.0, 27.0, 1)
+/// ];
+///
+/// assert!(result == expected);
+/// ```
+pub fn get_defined_interval_classification<T: ToPrimitive, S: ToPrimitive>(
+    width: S,
+    data: &[T],
+) -> Classification {
+    try_get_defined_interval_classification(width, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Defined Interval algorithm given the width of each bin and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `width` - A float representing the width of each bin (e.g. 10000 for bins of 10,000 people)
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_defined_interval_classification;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0];
+///
+/// assert!(try_get_defined_interval_classification(0.0, &data).is_err());
+/// ```
+pub fn try_get_defined_interval_classification<T: ToPrimitive, S: ToPrimitive>(
+    width: S,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    DefinedInterval {
+        width: try_param_to_f64("width", width)?,
+        anchor: None,
+        max_classes: None,
+    }
+    .classify(data)
+}
+
+/// Returns a Classification object following the Defined Interval algorithm given the width of each bin and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `width` - A float representing the width of each bin (e.g. 10000 for bins of 10,000 people)
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_defined_interval_classification_with_policy<T: ToPrimitive, S: ToPrimitive>(
+    width: S,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    DefinedInterval {
+        width: try_param_to_f64("width", width)?,
+        anchor: None,
+        max_classes: None,
+    }
+    .classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Defined Interval algorithm given the width of each bin and a dataset
+///
+/// # Arguments
+///
+/// * `width` - A float representing the width of each bin (e.g. 10000 for bins of 10,000 people)
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The bins are anchored at the minimum of the data; use try_get_defined_interval_breaks_with_options to anchor them elsewhere
+/// * Panics on empty data, non-finite data or a width that isn't positive; use try_get_defined_interval_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_defined_interval_breaks;
+///
+/// let data: Vec<i32> = vec![3, 8, 15, 22, 40];
+///
+/// let result: Vec<f64> = get_defined_interval_breaks(10, &data);
+///
+/// assert_eq!(result, vec![13.0, 23.0, 33.0]);
+/// ```
+pub fn get_defined_interval_breaks<T: ToPrimitive, S: ToPrimitive>(
+    width: S,
+    data: &[T],
+) -> Vec<f64> {
+    try_get_defined_interval_breaks(width, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Defined Interval algorithm given the width of each bin and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `width` - A float representing the width of each bin (e.g. 10000 for bins of 10,000 people)
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_defined_interval_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![0.5, 1.0, 2.5];
+///
+/// assert_eq!(try_get_defined_interval_breaks(1.0, &data), Ok(vec![1.5]));
+/// assert_eq!(try_get_defined_interval_breaks(1.0, &Vec::<f64>::new()), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_defined_interval_breaks<T: ToPrimitive, S: ToPrimitive>(
+    width: S,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    try_get_defined_interval_breaks_with_options(width, None, None, data)
+}
+
+/// Returns a vector of breaks generated through the Defined Interval algorithm given the width of each bin and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `width` - A float representing the width of each bin (e.g. 10000 for bins of 10,000 people)
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_defined_interval_breaks, try_get_defined_interval_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_defined_interval_breaks_with_policy(2.0, &data, &policy), try_get_defined_interval_breaks(2.0, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_defined_interval_breaks_with_policy(2.0, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_defined_interval_breaks_with_policy<T: ToPrimitive, S: ToPrimitive>(
+    width: S,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    DefinedInterval {
+        width: try_param_to_f64("width", width)?,
+        anchor: None,
+        max_classes: None,
+    }
+    .breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Defined Interval algorithm given the width of each bin, an optional anchor, an optional maximum number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// The breaks are every value anchor + n * width (for any integer n) lying strictly within the range of the data
+///
+/// # Arguments
+///
+/// * `width` - A float representing the width of each bin (e.g. 10000 for bins of 10,000 people)
+/// * `anchor` - A value every break is offset from by a multiple of the width (e.g. 0 for breaks at round multiples of the width), or None to anchor the bins at the minimum of the data
+/// * `max_classes` - The maximum number of bins the width may produce, or None for the default limit of 1,000,000 bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * If all data points are equal, no breaks are produced
+/// * Returns ClassifyError::InvalidParameter if the width would produce more than max_classes bins, and ClassifyError::InvalidBinCount if max_classes is zero
+/// * Returns ClassifyError::InvalidParameter if the width is smaller than the float spacing at the magnitude of the anchor or the data
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_defined_interval_breaks_with_options;
+///
+/// let data: Vec<f64> = vec![1250.0, 4800.0, 12000.0, 23500.0, 31000.0];
+///
+/// assert_eq!(
+///     try_get_defined_interval_breaks_with_options(10000.0, Some(0.0), None, &data),
+///     Ok(vec![10000.0, 20000.0, 30000.0])
+/// );
+/// assert!(try_get_defined_interval_breaks_with_options(10.0, Some(0.0), Some(100), &data).is_err());
+/// ```
+pub fn try_get_defined_interval_breaks_with_options<T: ToPrimitive, S: ToPrimitive>(
+    width: S,
+    anchor: Option<f64>,
+    max_classes: Option<usize>,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    DefinedInterval {
+        width: try_param_to_f64("width", width)?,
+        anchor,
+        max_classes,
+    }
+    .breaks(data)
+}
+
+/// Returns the breaks of a DefinedInterval configuration on a dataset, or a ClassifyError if the configuration is invalid or produces too many bins
+pub(crate) fn calc_defined_interval_breaks<T: ToPrimitive>(
+    method: &DefinedInterval,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    validate_defined_interval(method)?;
+    let data = try_to_vec_f64(data)?;
+
+    let mut min_value = data[0];
+    let mut max_value = data[0];
+    for item in &data {
+        if *item < min_value {
+            min_value = *item;
+        } else if *item > max_value {
+            max_value = *item;
+        }
+    }
+
+    select_defined_interval_breaks(method, min_value, max_value)
+}
+
+/// Returns the breaks of a DefinedInterval configuration on a SortedData object, or a ClassifyError if the configuration is invalid or produces too many bins
+pub(crate) fn calc_defined_interval_breaks_sorted(
+    method: &DefinedInterval,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    validate_defined_interval(method)?;
+
+    select_defined_interval_breaks(method, sorted.min(), sorted.max())
+}
+
+/// Returns a ClassifyError if the width of a DefinedInterval configuration isn't a finite positive number, if its anchor isn't finite or if its maximum number of bins is zero
+fn validate_defined_interval(method: &DefinedInterval) -> Result<(), ClassifyError> {
+    if !method.width.is_finite() || method.width <= 0.0 {
+        return Err(ClassifyError::InvalidParameter(
+            "width must be a finite number greater than zero".to_string(),
+        ));
+    }
+    if let Some(anchor) = method.anchor {
+        try_param_to_f64("anchor", anchor)?;
+    }
+    if method.max_classes == Some(0) {
+        return Err(ClassifyError::InvalidBinCount(0));
+    }
+    Ok(())
+}
+
+/// Maximum number of bins a DefinedInterval configuration without max_classes may produce
+const MAX_DEFINED_INTERVAL_CLASSES: usize = 1_000_000;
+
+/// Returns the multiples of the width of a DefinedInterval configuration (offset from its anchor) lying strictly within a range, or a ClassifyError if the width is too small to be resolved at the magnitude of the range and anchor or if they produce too many bins
+fn select_defined_interval_breaks(
+    method: &DefinedInterval,
+    min_value: f64,
+    max_value: f64,
+) -> Result<Vec<f64>, ClassifyError> {
+    if min_value == max_value {
+        return Ok(vec![]);
+    }
+    let width = method.width;
+    let anchor = method.anchor.unwrap_or(min_value);
+
+    // Below the float spacing of the values involved, consecutive multiples of the width can't be told apart
+    let scale = anchor.abs().max(min_value.abs()).max(max_value.abs());
+    if width < scale * f64::EPSILON {
+        return Err(ClassifyError::InvalidParameter(format!(
+            "a width of {} is smaller than the float spacing at {}",
+            width, scale
+        )));
+    }
+
+    // The multiples of the width are estimated by division, then adjusted by at most one step to lie strictly within the range of the data
+    let mut first = ((min_value - anchor) / width).floor() + 1.0;
+    if anchor + (first - 1.0) * width > min_value {
+        first -= 1.0;
+    } else if anchor + first * width <= min_value {
+        first += 1.0;
+    }
+    let mut last = ((max_value - anchor) / width).ceil() - 1.0;
+    if anchor + (last + 1.0) * width < max_value {
+        last += 1.0;
+    } else if anchor + last * width >= max_value {
+        last -= 1.0;
+    }
+
+    let num_breaks = (last - first + 1.0).max(0.0);
+    let max_classes = method.max_classes.unwrap_or(MAX_DEFINED_INTERVAL_CLASSES);
+    if num_breaks + 1.0 > max_classes as f64 {
+        return Err(ClassifyError::InvalidParameter(format!(
+            "a width of {} produces {} classes, more than the maximum of {}",
+            width,
+            num_breaks + 1.0,
+            max_classes
+        )));
+    }
+
+    let mut breaks: Vec<f64> = vec![];
+    for i in 0..num_breaks as usize {
+        breaks.push(anchor + (first + i as f64) * width);
+    }
+
+    Ok(breaks)
+}
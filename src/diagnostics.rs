@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+/// Represents a diagnostic event emitted by a classification algorithm while it runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A random set of breaks was evaluated, along with its goodness of variance fit (GVF)
+    Permutation { index: usize, gvf: f64 },
+    /// A new best goodness of variance fit (GVF) was reached
+    BestGvf(f64),
+    /// An iteration of an iterative algorithm finished
+    Iteration(usize),
+    /// A level of a recursive algorithm was computed, along with its mean (the break it adds) and the number of values in its head
+    Level {
+        index: usize,
+        mean: f64,
+        head_size: usize,
+    },
+}
+
+/// Receives diagnostic events from classification algorithms
+///
+/// Any closure taking an `&Event` can be used as an Observer
+///
+/// # Examples
+///
+/// ```
+/// use classify::{ApproxJenks, Classifier, Event, JenksOptions};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+/// let mut permutations = 0;
+///
+/// let breaks = ApproxJenks { num_bins: 3, options: JenksOptions::default() }.breaks_observed(&data, &mut |event: &Event| {
+///     if let Event::Permutation { .. } = event {
+///         permutations += 1;
+///     }
+/// });
+///
+/// assert_eq!(breaks, Ok(vec![4.0, 7.0]));
+/// assert_eq!(permutations, 10000);
+/// ```
+pub trait Observer {
+    fn on_event(&mut self, event: &Event);
+}
+
+impl<F: FnMut(&Event)> Observer for F {
+    fn on_event(&mut self, event: &Event) {
+        self(event)
+    }
+}
+
+/// Summary of the diagnostics collected while generating breaks
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, HeadTail};
+///
+/// let data: Vec<f64> = vec![1.0/1.0, 1.0/2.0, 1.0/3.0, 1.0/4.0, 1.0/5.0,
+///                           1.0/6.0, 1.0/7.0, 1.0/8.0, 1.0/9.0, 1.0/10.0];
+///
+/// let (breaks, report) = HeadTail.breaks_with_report(&data).unwrap();
+///
+/// assert_eq!(breaks.len(), 2);
+/// assert_eq!(report.iterations, 2);
+/// assert_eq!(report.permutations, 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    /// Number of random sets of breaks evaluated
+    pub permutations: usize,
+    /// Best goodness of variance fit (GVF) reached, if the algorithm reports one
+    pub best_gvf: Option<f64>,
+    /// Number of iterations of an iterative algorithm, or levels of a recursive one
+    pub iterations: usize,
+    /// Time spent generating the breaks (zero on wasm32-unknown-unknown, which has no clock)
+    pub elapsed: Duration,
+}
+
+impl Observer for Report {
+    fn on_event(&mut self, event: &Event) {
+        match event {
+            Event::Permutation { .. } => self.permutations += 1,
+            Event::BestGvf(gvf) => {
+                if self.best_gvf.map_or(true, |best| *gvf > best) {
+                    self.best_gvf = Some(*gvf);
+                }
+            }
+            Event::Iteration(_) | Event::Level { .. } => self.iterations += 1,
+        }
+    }
+}
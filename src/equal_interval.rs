@@ -1,5 +1,9 @@
+use crate::classifier::{Classifier, EqualInterval};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
+use crate::utilities::try_to_vec_f64;
 use crate::utilities::Classification;
-use crate::utilities::{breaks_to_classification, to_vec_f64};
 use num_traits::ToPrimitive;
 
 /// Returns a Classification object following the Equal Interval Breaks algorithm given the desired number of bins and one-dimensional data
@@ -13,6 +17,8 @@ use num_traits::ToPrimitive;
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
 /// * If there is a wide enoguh gap in the data, this algorithm may produce one or more empty bins
+/// * Zero bins are treated as one bin (no breaks), as in earlier versions; try_get_equal_interval_classification returns ClassifyError::InvalidBinCount instead
+/// * Panics on empty data or non-finite data; use try_get_equal_interval_classification to handle these cases instead
 ///
 /// # Examples
 ///
@@ -24,10 +30,10 @@ use num_traits::ToPrimitive;
 /// let num_bins = 3;
 ///
 /// let result: Classification = get_equal_interval_classification(num_bins, &data);
-/// let expected: Classification = vec![
-///     Bin{bin_start: 0.0, bin_end: 1.0, count: 2},
-///     Bin{bin_start: 1.0, bin_end: 2.0, count: 2},
-///     Bin{bin_start: 2.0, bin_end: 3.0, count: 2}
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(0.0, 1.0, 2),
+///     Bin::new(1.0, 2.0, 2),
+///     Bin::new(2.0, 3.0, 2)
 /// ];
 ///
 /// assert!(result == expected);
@@ -36,8 +42,47 @@ pub fn get_equal_interval_classification<T: ToPrimitive>(
     num_bins: usize,
     data: &[T],
 ) -> Classification {
-    let breaks: Vec<f64> = get_equal_interval_breaks(num_bins, data);
-    breaks_to_classification(&breaks, data)
+    try_get_equal_interval_classification(num_bins.max(1), data)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Equal Interval Breaks algorithm given the desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_equal_interval_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f32> = vec![0.0, 0.5, 1.0, 1.5, 2.5, 3.0];
+///
+/// assert_eq!(try_get_equal_interval_classification(0, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_equal_interval_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    EqualInterval { num_bins }.classify(data)
+}
+
+/// Returns a Classification object following the Equal Interval Breaks algorithm given the desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_equal_interval_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    EqualInterval { num_bins }.classify_with_policy(data, policy)
 }
 
 /// Returns a vector of breaks generated through the Equal Interval Breaks algorithm given the desired number of bins and a dataset
@@ -51,6 +96,8 @@ pub fn get_equal_interval_classification<T: ToPrimitive>(
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
 /// * If there is a wide enoguh gap in the data, this algorithm may produce one or more empty bins
+/// * Zero bins are treated as one bin (no breaks), as in earlier versions; try_get_equal_interval_breaks returns ClassifyError::InvalidBinCount instead
+/// * Panics on empty data or non-finite data; use try_get_equal_interval_breaks to handle these cases instead
 ///
 /// # Examples
 ///
@@ -65,7 +112,35 @@ pub fn get_equal_interval_classification<T: ToPrimitive>(
 /// assert_eq!(result, vec![1.0, 2.0]);
 /// ```
 pub fn get_equal_interval_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64> {
-    let data = to_vec_f64(data);
+    try_get_equal_interval_breaks(num_bins.max(1), data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Equal Interval Breaks algorithm given the desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_equal_interval_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f32> = vec![0.0, 0.5, 1.0, 1.5, 2.5, 3.0];
+///
+/// assert_eq!(try_get_equal_interval_breaks(3, &data), Ok(vec![1.0, 2.0]));
+/// assert_eq!(try_get_equal_interval_breaks(3, &Vec::<f32>::new()), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_equal_interval_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let data = try_to_vec_f64(data)?;
 
     let mut min_value = data[0];
     let mut max_value = data[0];
@@ -77,10 +152,57 @@ pub fn get_equal_interval_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) ->
         }
     }
 
+    Ok(calc_equal_interval_breaks(num_bins, min_value, max_value))
+}
+
+/// Returns a vector of breaks generated through the Equal Interval Breaks algorithm given the desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_equal_interval_breaks, try_get_equal_interval_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_equal_interval_breaks_with_policy(3, &data, &policy), try_get_equal_interval_breaks(3, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_equal_interval_breaks_with_policy(3, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_equal_interval_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    EqualInterval { num_bins }.breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Equal Interval Breaks algorithm given the desired number of bins and a SortedData object, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_equal_interval_breaks_sorted(
+    num_bins: usize,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    Ok(calc_equal_interval_breaks(
+        num_bins,
+        sorted.min(),
+        sorted.max(),
+    ))
+}
+
+/// Returns the breaks dividing the range between a minimum and a maximum into the given number of bins of equal width
+fn calc_equal_interval_breaks(num_bins: usize, min_value: f64, max_value: f64) -> Vec<f64> {
     let mut result: Vec<f64> = vec![];
     for i in 1..num_bins {
         result.push(min_value + (max_value - min_value) * (i as f64 / num_bins as f64));
     }
-
     result
 }
@@ -0,0 +1,36 @@
+use std::fmt;
+
+/// Represents the ways in which a classification function can fail
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClassifyError {
+    /// The input dataset contains no values
+    EmptyInput,
+    /// The value at the given index is NaN or infinite
+    NonFiniteValue(usize),
+    /// The requested number of bins cannot be used by the algorithm
+    InvalidBinCount(usize),
+    /// A parameter other than the number of bins is out of range, along with a description of the problem
+    InvalidParameter(String),
+    /// The value at the given index could not be converted to f64
+    ConversionFailed(usize),
+}
+
+impl fmt::Display for ClassifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassifyError::EmptyInput => write!(f, "input data is empty"),
+            ClassifyError::NonFiniteValue(idx) => {
+                write!(f, "value at index {} is NaN or infinite", idx)
+            }
+            ClassifyError::InvalidBinCount(num_bins) => {
+                write!(f, "invalid number of bins: {}", num_bins)
+            }
+            ClassifyError::InvalidParameter(msg) => write!(f, "invalid parameter: {}", msg),
+            ClassifyError::ConversionFailed(idx) => {
+                write!(f, "value at index {} could not be converted to f64", idx)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClassifyError {}
@@ -0,0 +1,274 @@
+use num_traits::ToPrimitive;
+
+use crate::classifier::{Classifier, GeometricInterval};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
+use crate::utilities::{try_to_weighted_vals, unique_to_weighted_vals};
+use crate::utilities::{Classification, WeightedVal};
+
+/// The number of candidate multipliers tried on each side of 1 (on a logarithmic scale)
+const NUM_CANDIDATES: usize = 1000;
+
+/// Returns a Classification object following the Geometric Interval algorithm given the desired number of bins and one-dimensional data
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * If all data points are equal, a single bin is produced
+/// * Panics on empty data, non-finite data or zero bins; use try_get_geometric_interval_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_geometric_interval_classification;
+///
+/// let data: Vec<f64> = vec![0.0, 1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 40.0, 80.0];
+///
+/// let result = get_geometric_interval_classification(3, &data);
+///
+/// assert_eq!(result.len(), 3);
+/// assert_eq!(result.iter().map(|bin| bin.count).collect::<Vec<u64>>(), vec![3, 3, 3]);
+/// ```
+pub fn get_geometric_interval_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Classification {
+    try_get_geometric_interval_classification(num_bins, data)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Geometric Interval algorithm given the desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_geometric_interval_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0];
+///
+/// assert_eq!(try_get_geometric_interval_classification(0, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_geometric_interval_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    GeometricInterval { num_bins }.classify(data)
+}
+
+/// Returns a Classification object following the Geometric Interval algorithm given the desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_geometric_interval_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    GeometricInterval { num_bins }.classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Geometric Interval algorithm given the desired number of bins and a dataset
+///
+/// The width of each bin is the width of the previous bin times a constant multiplier, which is chosen to minimize the sum of the squared number of data points in each bin. Widths are measured from the minimum of the data, so data containing negative values or crossing zero is handled like any other data
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * If all data points are equal, no breaks are produced
+/// * The multiplier is searched on a logarithmic grid, and among equally good multipliers the one closest to 1 (equal intervals) is kept
+/// * Panics on empty data, non-finite data or zero bins; use try_get_geometric_interval_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_geometric_interval_breaks;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
+///
+/// let result: Vec<f64> = get_geometric_interval_breaks(3, &data);
+///
+/// assert_eq!(result, vec![2.0, 3.0]);
+/// ```
+pub fn get_geometric_interval_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64> {
+    try_get_geometric_interval_breaks(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Geometric Interval algorithm given the desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_geometric_interval_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![-10.0, -9.0, -8.0, -4.0, 8.0];
+///
+/// let result = try_get_geometric_interval_breaks(2, &data).unwrap();
+///
+/// assert!(result[0] > -8.0 && result[0] <= -4.0);
+/// assert_eq!(try_get_geometric_interval_breaks(2, &[f64::NAN]), Err(ClassifyError::NonFiniteValue(0)));
+/// ```
+pub fn try_get_geometric_interval_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    try_get_geometric_interval_breaks_sorted(num_bins, &SortedData::try_new_without_sums(data)?)
+}
+
+/// Returns a vector of breaks generated through the Geometric Interval algorithm given the desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_geometric_interval_breaks, try_get_geometric_interval_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_geometric_interval_breaks_with_policy(3, &data, &policy), try_get_geometric_interval_breaks(3, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_geometric_interval_breaks_with_policy(3, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_geometric_interval_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    GeometricInterval { num_bins }.breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Geometric Interval algorithm given the desired number of bins and a SortedData object, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_geometric_interval_breaks_sorted(
+    num_bins: usize,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    Ok(calc_geometric_interval_breaks(
+        &unique_to_weighted_vals(sorted.unique_vals()),
+        num_bins,
+    ))
+}
+
+/// Returns a vector of breaks generated through the Geometric Interval algorithm given the desired number of bins and a weighted dataset, balancing the total weight of the bins, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_geometric_interval_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+
+    Ok(calc_geometric_interval_breaks(&weighted_vals, num_bins))
+}
+
+/// Returns the geometric breaks whose bins hold the most even total weights given sorted weighted unique values and the desired number of bins (at least one)
+fn calc_geometric_interval_breaks(weighted_vals: &[WeightedVal], num_bins: usize) -> Vec<f64> {
+    let num_vals = weighted_vals.len();
+    let min_value = weighted_vals[0].val;
+    let range = weighted_vals[num_vals - 1].val - min_value;
+    if num_bins == 1 || range == 0.0 {
+        return vec![];
+    }
+
+    // The multiplier is bounded so that the narrowest bin is never much narrower than the smallest gap between two unique values
+    let mut min_gap = range;
+    for i in 1..num_vals {
+        let gap = weighted_vals[i].val - weighted_vals[i - 1].val;
+        if gap < min_gap {
+            min_gap = gap;
+        }
+    }
+    let max_log_multiplier = (range / min_gap).ln() / (num_bins - 1) as f64;
+
+    let mut best_breaks = calc_geometric_breaks(min_value, range, 1.0, num_bins);
+    let mut best_score = calc_weight_score(&best_breaks, weighted_vals);
+    for i in 1..=NUM_CANDIDATES {
+        let log_multiplier = max_log_multiplier * i as f64 / NUM_CANDIDATES as f64;
+        for multiplier in [log_multiplier.exp(), (-log_multiplier).exp()] {
+            let breaks = calc_geometric_breaks(min_value, range, multiplier, num_bins);
+            let score = calc_weight_score(&breaks, weighted_vals);
+            if score < best_score {
+                best_score = score;
+                best_breaks = breaks;
+            }
+        }
+    }
+
+    best_breaks
+}
+
+/// Returns the breaks of bins spanning a range from a minimum whose widths grow by a constant multiplier
+fn calc_geometric_breaks(min_value: f64, range: f64, multiplier: f64, num_bins: usize) -> Vec<f64> {
+    let mut widths: Vec<f64> = vec![1.0];
+    for i in 1..num_bins {
+        widths.push(widths[i - 1] * multiplier);
+    }
+    let total: f64 = widths.iter().sum();
+
+    let mut breaks: Vec<f64> = vec![];
+    let mut offset = 0.0;
+    for width in widths.iter().take(num_bins - 1) {
+        offset += width / total;
+        breaks.push(min_value + range * offset);
+    }
+    breaks
+}
+
+/// Returns the sum of the squared total weight in each bin given a set of breaks and sorted weighted unique values
+fn calc_weight_score(breaks: &[f64], weighted_vals: &[WeightedVal]) -> f64 {
+    let mut score = 0.0;
+    let mut weight = 0.0;
+    let mut breaks = breaks.iter().peekable();
+    for item in weighted_vals {
+        while let Some(brk) = breaks.peek() {
+            if item.val < **brk {
+                break;
+            }
+            score += weight * weight;
+            weight = 0.0;
+            breaks.next();
+        }
+        weight += item.weight;
+    }
+    score += weight * weight;
+    score
+}
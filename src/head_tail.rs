@@ -1,5 +1,10 @@
+use crate::classifier::{Classifier, HeadTail};
+use crate::diagnostics::{Event, Observer};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
 use crate::utilities::Classification;
-use crate::utilities::{breaks_to_classification, to_vec_f64};
+use crate::utilities::{try_to_weighted_vals, WeightedVal};
 use num_traits::ToPrimitive;
 
 /// Returns a Classification object following the Head-Tail Breaks algorithm given one-dimensional data
@@ -11,6 +16,7 @@ use num_traits::ToPrimitive;
 /// # Edge Cases
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * Panics on empty or non-finite data; use try_get_head_tail_classification to handle these cases instead
 ///
 /// # Examples
 ///
@@ -22,17 +28,51 @@ use num_traits::ToPrimitive;
 ///                           1.0/6.0, 1.0/7.0, 1.0/8.0, 1.0/9.0, 1.0/10.0];
 ///
 /// let result: Classification = get_head_tail_classification(&data);
-/// let expected: Classification = vec![
-///     Bin{bin_start: 0.1, bin_end: 0.2928968253968254, count: 7},
-///     Bin{bin_start: 0.2928968253968254, bin_end: 0.611111111111111, count: 2},
-///     Bin{bin_start: 0.611111111111111, bin_end: 1.0, count: 1}
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(0.1, 0.2928968253968254, 7),
+///     Bin::new(0.2928968253968254, 0.611111111111111, 2),
+///     Bin::new(0.611111111111111, 1.0, 1)
 /// ];
 ///
 /// assert!(result == expected);
 /// ```
 pub fn get_head_tail_classification<T: ToPrimitive>(data: &[T]) -> Classification {
-    let breaks: Vec<f64> = get_head_tail_breaks(data);
-    breaks_to_classification(&breaks, data)
+    try_get_head_tail_classification(data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Head-Tail Breaks algorithm given one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_head_tail_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![];
+///
+/// assert_eq!(try_get_head_tail_classification(&data), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_head_tail_classification<T: ToPrimitive>(
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    HeadTail.classify(data)
+}
+
+/// Returns a Classification object following the Head-Tail Breaks algorithm given one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_head_tail_classification_with_policy<T: ToPrimitive>(
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    HeadTail.classify_with_policy(data, policy)
 }
 
 /// Returns a vector of breaks generated through the Head-Tail Breaks algorithm given a dataset
@@ -44,6 +84,7 @@ pub fn get_head_tail_classification<T: ToPrimitive>(data: &[T]) -> Classificatio
 /// # Edge Cases
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * Panics on empty or non-finite data; use try_get_head_tail_breaks to handle these cases instead
 ///
 /// # Examples
 ///
@@ -58,22 +99,79 @@ pub fn get_head_tail_classification<T: ToPrimitive>(data: &[T]) -> Classificatio
 /// assert_eq!(result, vec![0.2928968253968254, 0.611111111111111]);
 /// ```
 pub fn get_head_tail_breaks<T: ToPrimitive>(data: &[T]) -> Vec<f64> {
-    let data = to_vec_f64(data);
+    try_get_head_tail_breaks(data).unwrap_or_else(|err| panic!("{}", err))
+}
 
-    let mut breaks: Vec<f64> = vec![];
+/// Returns a vector of breaks generated through the Head-Tail Breaks algorithm given a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_head_tail_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NEG_INFINITY];
+///
+/// assert_eq!(try_get_head_tail_breaks(&data), Err(ClassifyError::NonFiniteValue(2)));
+/// ```
+pub fn try_get_head_tail_breaks<T: ToPrimitive>(data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+    try_get_head_tail_breaks_observed(data, &mut |_: &Event| {})
+}
 
-    let mut sorted_data: Vec<f64> = vec![];
-    for item in data.iter().take(data.len()) {
-        sorted_data.push(*item);
-    }
-    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    head_tail_recursion(&sorted_data, &mut breaks);
+/// Returns a vector of breaks generated through the Head-Tail Breaks algorithm given a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_head_tail_breaks, try_get_head_tail_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_head_tail_breaks_with_policy(&data, &policy), try_get_head_tail_breaks(&[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_head_tail_breaks_with_policy(&data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_head_tail_breaks_with_policy<T: ToPrimitive>(
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    HeadTail.breaks_with_policy(data, policy)
+}
 
-    breaks
+/// Returns a vector of breaks generated through the Head-Tail Breaks algorithm given a dataset, reporting each level of recursion to an Observer
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `observer` - A mutable reference to an Observer that receives an `Event::Level` as each level of recursion is computed
+pub fn try_get_head_tail_breaks_observed<T: ToPrimitive>(
+    data: &[T],
+    observer: &mut dyn Observer,
+) -> Result<Vec<f64>, ClassifyError> {
+    try_get_head_tail_breaks_sorted(&SortedData::try_new_without_sums(data)?, observer)
+}
+
+/// Returns a vector of breaks generated through the Head-Tail Breaks algorithm given a SortedData object, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_head_tail_breaks_sorted(
+    sorted: &SortedData,
+    observer: &mut dyn Observer,
+) -> Result<Vec<f64>, ClassifyError> {
+    let mut breaks: Vec<f64> = vec![];
+    head_tail_recursion(sorted.values(), &mut breaks, observer);
+    Ok(breaks)
 }
 
-/// Recursive function used by get_head_tail_breaks that populates a vector of breaks according to the head-tail breaks algorithm
-pub fn head_tail_recursion(data: &Vec<f64>, breaks: &mut Vec<f64>) {
+/// Recursive function used by get_head_tail_breaks that populates a vector of breaks according to the head-tail breaks algorithm, reporting each level to an Observer
+pub fn head_tail_recursion(data: &[f64], breaks: &mut Vec<f64>, observer: &mut dyn Observer) {
     let mut mean: f64 = 0.0;
     for val in data {
         mean += val
@@ -87,7 +185,7 @@ pub fn head_tail_recursion(data: &Vec<f64>, breaks: &mut Vec<f64>) {
     let mut high = data.len();
     let mut break_idx = 'outer: loop {
         let mid = (low + high) / 2;
-        if mean < data[mid] as f64 {
+        if mean < data[mid] {
             high = mid;
         } else if mean == data[mid] {
             break 'outer mid;
@@ -102,12 +200,47 @@ pub fn head_tail_recursion(data: &Vec<f64>, breaks: &mut Vec<f64>) {
         break_idx -= 1;
     }
 
-    let head: Vec<f64> = data[break_idx..].to_vec();
+    let head: &[f64] = &data[break_idx..];
+    observer.on_event(&Event::Level {
+        index: breaks.len() - 1,
+        mean,
+        head_size: head.len(),
+    });
 
     if !head.is_empty()
         && (head.len() as f64) / (data.len() as f64) <= 0.4
         && head[0] != head[head.len() - 1]
     {
-        head_tail_recursion(&head, breaks);
+        head_tail_recursion(head, breaks, observer);
     }
 }
+
+/// Returns a vector of breaks generated through the Head-Tail Breaks algorithm given a weighted dataset, splitting every level at its weighted mean and recursing while the head holds no more than 40% of the weight, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_head_tail_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+
+    let mut breaks: Vec<f64> = vec![];
+    let mut level: &[WeightedVal] = &weighted_vals;
+    loop {
+        let mut total_weight = 0.0;
+        let mut mean = 0.0;
+        for item in level {
+            total_weight += item.weight;
+            mean += item.val * item.weight;
+        }
+        mean /= total_weight;
+        breaks.push(mean);
+
+        let head = &level[level.partition_point(|item| item.val < mean)..];
+        let head_weight: f64 = head.iter().map(|item| item.weight).sum();
+        if head.len() < 2 || head_weight / total_weight > 0.4 {
+            break;
+        }
+        level = head;
+    }
+
+    Ok(breaks)
+}
@@ -1,5 +1,11 @@
+use crate::classifier::{Classifier, Hinge};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::percentiles::QuantileDefinition;
+use crate::percentiles::{calc_cumulative_weights, calc_quantile, calc_weighted_quantile};
+use crate::sorted_data::SortedData;
 use crate::utilities::Classification;
-use crate::utilities::{breaks_to_classification, to_vec_f64};
+use crate::utilities::{try_param_to_f64, try_to_weighted_vals};
 use num_traits::ToPrimitive;
 
 /// Returns a Classification object following the Hinge Breaks algorithm given the desired number of bins and one-dimensional data
@@ -13,6 +19,7 @@ use num_traits::ToPrimitive;
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
 /// * If the data doesn't have outliers below/above the hinges, the algorithm may not produce all six bins
+/// * Panics on empty data, non-finite data or a negative hinge coefficient; use try_get_hinge_classification to handle these cases instead
 ///
 /// # Examples
 ///
@@ -24,13 +31,13 @@ use num_traits::ToPrimitive;
 /// let hinge_coefficient = 1.5;
 ///
 /// let result: Classification = get_hinge_classification(hinge_coefficient, &data);
-/// let expected: Classification = vec![
-///     Bin{bin_start: 0.0, bin_end: 3.0, count: 2},
-///     Bin{bin_start: 3.0, bin_end: 10.5, count: 1},
-///     Bin{bin_start: 10.5, bin_end: 13.0, count: 2},
-///     Bin{bin_start: 13.0, bin_end: 15.5, count: 3},
-///     Bin{bin_start: 15.5, bin_end: 23.0, count: 2},
-///     Bin{bin_start: 23.0, bin_end: 25.0, count: 1}
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(0.0, 3.0, 2),
+///     Bin::new(3.0, 10.5, 1),
+///     Bin::new(10.5, 13.0, 2),
+///     Bin::new(13.0, 15.5, 3),
+///     Bin::new(15.5, 23.0, 2),
+///     Bin::new(23.0, 25.0, 1)
 /// ];
 ///
 /// assert!(result == expected);
@@ -39,8 +46,52 @@ pub fn get_hinge_classification<T: ToPrimitive, S: ToPrimitive>(
     hinge_coefficient: S,
     data: &[T],
 ) -> Classification {
-    let breaks: Vec<f64> = get_hinge_breaks(hinge_coefficient, data);
-    breaks_to_classification(&breaks, data)
+    try_get_hinge_classification(hinge_coefficient, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Hinge Breaks algorithm given the desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `hinge_coefficient` - A coefficient representing the size of the hinge as a multiple of the data's IQR (usually 1.5 or 3)
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_hinge_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f32> = vec![];
+///
+/// assert_eq!(try_get_hinge_classification(1.5, &data), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_hinge_classification<T: ToPrimitive, S: ToPrimitive>(
+    hinge_coefficient: S,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    Hinge {
+        hinge_coefficient: try_param_to_f64("hinge_coefficient", hinge_coefficient)?,
+    }
+    .classify(data)
+}
+
+/// Returns a Classification object following the Hinge Breaks algorithm given the desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `hinge_coefficient` - A coefficient representing the size of the hinge as a multiple of the data's IQR (usually 1.5 or 3)
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_hinge_classification_with_policy<T: ToPrimitive, S: ToPrimitive>(
+    hinge_coefficient: S,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    Hinge {
+        hinge_coefficient: try_param_to_f64("hinge_coefficient", hinge_coefficient)?,
+    }
+    .classify_with_policy(data, policy)
 }
 
 /// Returns a vector of breaks generated through the Hinge Breaks algorithm given the desired number of bins and a dataset
@@ -54,6 +105,7 @@ pub fn get_hinge_classification<T: ToPrimitive, S: ToPrimitive>(
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
 /// * If the data doesn't have outliers below/above the hinges, the algorithm may not produce all six bins
+/// * Panics on empty data, non-finite data or a negative hinge coefficient; use try_get_hinge_breaks to handle these cases instead
 ///
 /// # Examples
 ///
@@ -71,23 +123,135 @@ pub fn get_hinge_breaks<T: ToPrimitive, S: ToPrimitive>(
     hinge_coefficient: S,
     data: &[T],
 ) -> Vec<f64> {
-    let hinge_coefficient = hinge_coefficient.to_f64().unwrap();
-    let data = to_vec_f64(data);
+    try_get_hinge_breaks(hinge_coefficient, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Hinge Breaks algorithm given the desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `hinge_coefficient` - A coefficient representing the size of the hinge as a multiple of the data's IQR (usually 1.5 or 3)
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_hinge_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<usize> = vec![0, 1, 10, 11, 12, 13, 14, 15, 16, 20, 25];
+///
+/// assert_eq!(try_get_hinge_breaks(1.5, &data), Ok(vec![3.0, 10.5, 13.0, 15.5, 23.0]));
+/// assert!(try_get_hinge_breaks(f64::NAN, &data).is_err());
+/// ```
+pub fn try_get_hinge_breaks<T: ToPrimitive, S: ToPrimitive>(
+    hinge_coefficient: S,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    let hinge_coefficient = try_hinge_coefficient(hinge_coefficient)?;
 
-    let num_vals = data.len();
+    try_get_hinge_breaks_sorted(hinge_coefficient, &SortedData::try_new_without_sums(data)?)
+}
 
-    let mut sorted_data: Vec<f64> = vec![];
-    for item in data.iter().take(num_vals) {
-        sorted_data.push(*item);
+/// Returns a vector of breaks generated through the Hinge Breaks algorithm given the desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `hinge_coefficient` - A coefficient representing the size of the hinge as a multiple of the data's IQR (usually 1.5 or 3)
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_hinge_breaks, try_get_hinge_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_hinge_breaks_with_policy(1.5, &data, &policy), try_get_hinge_breaks(1.5, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_hinge_breaks_with_policy(1.5, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_hinge_breaks_with_policy<T: ToPrimitive, S: ToPrimitive>(
+    hinge_coefficient: S,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    Hinge {
+        hinge_coefficient: try_param_to_f64("hinge_coefficient", hinge_coefficient)?,
     }
-    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    .breaks_with_policy(data, policy)
+}
 
-    let min_val = sorted_data[0];
-    let max_val = sorted_data[num_vals - 1];
+/// Returns a vector of breaks generated through the Hinge Breaks algorithm given the hinge coefficient and a SortedData object, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_hinge_breaks_sorted(
+    hinge_coefficient: f64,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    let hinge_coefficient = try_hinge_coefficient(hinge_coefficient)?;
+    let sorted_data = sorted.values();
 
-    let perc_25 = percentile(25, &sorted_data);
-    let perc_50 = percentile(50, &sorted_data);
-    let perc_75 = percentile(75, &sorted_data);
+    let quartiles = [
+        percentile(25, sorted_data),
+        percentile(50, sorted_data),
+        percentile(75, sorted_data),
+    ];
+
+    Ok(calc_hinge_breaks(
+        hinge_coefficient,
+        quartiles,
+        sorted.min(),
+        sorted.max(),
+    ))
+}
+
+/// Returns a vector of breaks generated through the Hinge Breaks algorithm given the hinge coefficient and a weighted dataset, using weighted quartiles (treating weights as numbers of occurrences), or a ClassifyError if the input is invalid
+pub(crate) fn try_get_hinge_breaks_weighted<T: ToPrimitive, S: ToPrimitive, W: ToPrimitive>(
+    hinge_coefficient: S,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    let hinge_coefficient = try_hinge_coefficient(hinge_coefficient)?;
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+    let cum_weights = calc_cumulative_weights(&weighted_vals);
+
+    let quartile = |perc: f64| -> f64 {
+        calc_weighted_quantile(
+            &weighted_vals,
+            &cum_weights,
+            perc,
+            100.0,
+            QuantileDefinition::Type7,
+        )
+    };
+    let quartiles = [quartile(25.0), quartile(50.0), quartile(75.0)];
+
+    Ok(calc_hinge_breaks(
+        hinge_coefficient,
+        quartiles,
+        weighted_vals[0].val,
+        weighted_vals[weighted_vals.len() - 1].val,
+    ))
+}
+
+/// Translates the hinge coefficient to f64, returning an error if it can't be converted, isn't finite or is negative
+fn try_hinge_coefficient<S: ToPrimitive>(hinge_coefficient: S) -> Result<f64, ClassifyError> {
+    let hinge_coefficient = try_param_to_f64("hinge_coefficient", hinge_coefficient)?;
+    if hinge_coefficient < 0.0 {
+        return Err(ClassifyError::InvalidParameter(
+            "hinge_coefficient must not be negative".to_string(),
+        ));
+    }
+    Ok(hinge_coefficient)
+}
+
+/// Returns the quartiles of a dataset along with the hinges (the lower and upper quartiles extended by a multiple of the IQR) that lie within the range of the data
+fn calc_hinge_breaks(
+    hinge_coefficient: f64,
+    [perc_25, perc_50, perc_75]: [f64; 3],
+    min_val: f64,
+    max_val: f64,
+) -> Vec<f64> {
     let iqr = perc_75 - perc_25;
     let hinge = iqr * hinge_coefficient;
 
@@ -101,28 +265,10 @@ pub fn get_hinge_breaks<T: ToPrimitive, S: ToPrimitive>(
     if perc_75 + hinge < max_val {
         breaks.push(perc_75 + hinge);
     }
-
     breaks
 }
 
-/// Calculates percentiles of a given dataset
-pub fn percentile(perc: u8, data: &Vec<f64>) -> f64 {
-    let num_vals = data.len();
-
-    let mut sorted_data: Vec<f64> = vec![];
-    for item in data.iter().take(num_vals) {
-        sorted_data.push(*item);
-    }
-    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-    let rank = (perc as f64 / 100.0) * (num_vals as f64 - 1.0);
-
-    if rank as usize == num_vals - 1 {
-        sorted_data[num_vals - 1]
-    } else {
-        let rank_int = rank as usize;
-        let rank_dec = rank - rank_int as f64;
-
-        sorted_data[rank_int] + rank_dec * (sorted_data[rank_int + 1] - sorted_data[rank_int])
-    }
+/// Calculates a percentile of a given sorted dataset by linear interpolation (QuantileDefinition::Type7)
+pub fn percentile(perc: u8, sorted_data: &[f64]) -> f64 {
+    calc_quantile(sorted_data, perc as f64, 100.0, QuantileDefinition::Type7)
 }
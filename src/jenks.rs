@@ -1,13 +1,23 @@
 use num_traits::ToPrimitive;
 use rand::prelude::*;
 use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
 
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
-use crate::utilities::{
-    breaks_to_classification, create_unique_val_mapping, to_vec_f64, unique_to_normal_breaks,
-};
-use crate::utilities::{Classification, UniqueVal};
+use crate::classifier::{ApproxJenks, Classifier, Jenks, JenksCaspall};
+use crate::diagnostics::{Event, Observer};
+use crate::equal_interval::{try_get_equal_interval_breaks, try_get_equal_interval_breaks_sorted};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::parallel::map_slice;
+use crate::percentiles::QuantileDefinition;
+use crate::quantile::{calc_weighted_quantile_breaks, try_get_quantile_breaks_sorted};
+use crate::sorted_data::SortedData;
+use crate::utilities::{try_to_weighted_vals, unique_to_weighted_vals};
+use crate::utilities::{unique_to_normal_breaks, PrefixSums};
+use crate::utilities::{Classification, UniqueVal, WeightedVal};
 
 /// Returns a Classification object following the Jenks Natural Breaks algorithm given the desired number of bins and one-dimensional data
 ///
@@ -20,34 +30,73 @@ use crate::utilities::{Classification, UniqueVal};
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
 /// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * Panics on empty data, non-finite data or zero bins; use try_get_jenks_classification to handle these cases instead
 ///
 /// # Examples
 ///
 /// ```
 /// use classify::get_jenks_classification;
 /// use classify::{Classification, Bin};
-/// use rand::prelude::*;
-/// use rand::rngs::StdRng;
 ///
 /// let data: Vec<usize> = vec![1, 2, 4, 5, 7, 8];
 /// let num_bins = 3;
 ///
 /// let result: Classification = get_jenks_classification(num_bins, &data);
-/// let expected: Classification = vec![
-///     Bin{bin_start: 1.0, bin_end: 4.0, count: 2},
-///     Bin{bin_start: 4.0, bin_end: 7.0, count: 2},
-///     Bin{bin_start: 7.0, bin_end: 8.0, count: 2}
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(1.0, 4.0, 2),
+///     Bin::new(4.0, 7.0, 2),
+///     Bin::new(7.0, 8.0, 2)
 /// ];
 ///
 /// assert!(result == expected);
 /// ```
 pub fn get_jenks_classification<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Classification {
-    let breaks: Vec<f64> = get_jenks_breaks(num_bins, data);
-    breaks_to_classification(&breaks, data)
+    try_get_jenks_classification(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Jenks Natural Breaks algorithm given the desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_jenks_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![];
+///
+/// assert_eq!(try_get_jenks_classification(3, &data), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_jenks_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    Jenks { num_bins }.classify(data)
+}
+
+/// Returns a Classification object following the Jenks Natural Breaks algorithm given the desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_jenks_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    Jenks { num_bins }.classify_with_policy(data, policy)
 }
 
 /// Returns a vector of breaks generated through the Jenks Natural Breaks algorithm given the desired number of bins and a dataset
 ///
+/// The breaks are found with Fisher's exact dynamic programming algorithm, so they are guaranteed to minimize the total within-class sum of squared deviations
+///
 /// # Arguments
 ///
 /// * `num_bins` - The desired number of bins
@@ -57,13 +106,13 @@ pub fn get_jenks_classification<T: ToPrimitive>(num_bins: usize, data: &[T]) ->
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
 /// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * Runtime grows with the square of the number of unique values in the dataset, so get_approx_jenks_breaks may be preferable for very large datasets
+/// * Panics on empty data, non-finite data or zero bins; use try_get_jenks_breaks to handle these cases instead
 ///
 /// # Examples
 ///
 /// ```
 /// use classify::get_jenks_breaks;
-/// use rand::prelude::*;
-/// use rand::rngs::StdRng;
 ///
 /// let data: Vec<i8> = vec![1, 2, 4, 5, 7, 8];
 /// let num_bins = 3;
@@ -73,60 +122,1187 @@ pub fn get_jenks_classification<T: ToPrimitive>(num_bins: usize, data: &[T]) ->
 /// assert_eq!(result, vec![4.0, 7.0]);
 /// ```
 pub fn get_jenks_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64> {
-    let data = to_vec_f64(data);
+    try_get_jenks_breaks(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
 
-    let num_vals = data.len();
+/// Returns a vector of breaks generated through the Jenks Natural Breaks algorithm given the desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_jenks_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(try_get_jenks_breaks(3, &data), Ok(vec![4.0, 7.0]));
+/// assert_eq!(try_get_jenks_breaks(0, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_jenks_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    try_get_jenks_breaks_observed(num_bins, data, &mut |_: &Event| {})
+}
 
-    let mut sorted_data: Vec<f64> = vec![];
-    for item in data.iter().take(num_vals) {
-        sorted_data.push(*item);
+/// Returns a vector of breaks generated through the Jenks Natural Breaks algorithm given the desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_jenks_breaks, try_get_jenks_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_jenks_breaks_with_policy(3, &data, &policy), try_get_jenks_breaks(3, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_jenks_breaks_with_policy(3, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_jenks_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    Jenks { num_bins }.breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Jenks Natural Breaks algorithm given the desired number of bins and a dataset, reporting the GVF of the result to an Observer
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `observer` - A mutable reference to an Observer that receives diagnostic events
+pub fn try_get_jenks_breaks_observed<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    observer: &mut dyn Observer,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    try_get_jenks_breaks_sorted(num_bins, &SortedData::try_new_without_sums(data)?, observer)
+}
+
+/// Returns a vector of breaks generated through the Jenks Natural Breaks algorithm given the desired number of bins and a SortedData object, reporting the GVF of the result to an Observer, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_jenks_breaks_sorted(
+    num_bins: usize,
+    sorted: &SortedData,
+    observer: &mut dyn Observer,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let sorted_data = sorted.values();
+    let unique_val_map = sorted.unique_vals();
+
+    let true_num_bins = std::cmp::min(unique_val_map.len(), num_bins);
+    if true_num_bins < 2 {
+        return Ok(vec![]);
     }
-    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
 
-    let mut unique_val_map: Vec<UniqueVal> = vec![];
-    create_unique_val_mapping(&mut unique_val_map, &sorted_data);
+    let unique_breaks = calc_fisher_jenks_breaks(unique_val_map, true_num_bins);
+
+    let mut normal_breaks: Vec<usize> = vec![];
+    unique_to_normal_breaks(&unique_breaks, unique_val_map, &mut normal_breaks);
+    let gssd = calc_gssd(sorted_data);
+    observer.on_event(&Event::BestGvf(calc_gvf(
+        &normal_breaks,
+        sorted_data,
+        &gssd,
+    )));
+
+    let mut nat_breaks: Vec<f64> = vec![];
+    for item in unique_breaks {
+        nat_breaks.push(unique_val_map[item].val);
+    }
+
+    Ok(nat_breaks)
+}
+
+/// Returns a vector of breaks generated through the Jenks Natural Breaks algorithm given the desired number of bins and a weighted dataset, minimizing the weighted sum of squared deviations within bins, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_jenks_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+
+    let true_num_bins = std::cmp::min(weighted_vals.len(), num_bins);
+    if true_num_bins < 2 {
+        return Ok(vec![]);
+    }
+
+    let unique_breaks = calc_weighted_fisher_jenks_breaks(&weighted_vals, true_num_bins);
+
+    Ok(unique_breaks
+        .iter()
+        .map(|item| weighted_vals[*item].val)
+        .collect())
+}
+
+/// Returns a Classification object following an approximation of the Jenks Natural Breaks algorithm given the desired number of bins and one-dimensional data
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * The result is not guaranteed to be optimal; use get_jenks_classification for exact natural breaks
+/// * Panics on empty data, non-finite data or zero bins; use try_get_approx_jenks_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_approx_jenks_classification;
+/// use classify::{Classification, Bin};
+///
+/// let data: Vec<usize> = vec![1, 2, 4, 5, 7, 8];
+/// let num_bins = 3;
+///
+/// let result: Classification = get_approx_jenks_classification(num_bins, &data);
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(1.0, 4.0, 2),
+///     Bin::new(4.0, 7.0, 2),
+///     Bin::new(7.0, 8.0, 2)
+/// ];
+///
+/// assert!(result == expected);
+/// ```
+pub fn get_approx_jenks_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Classification {
+    try_get_approx_jenks_classification(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following an approximation of the Jenks Natural Breaks algorithm given the desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_approx_jenks_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, f64::INFINITY];
+///
+/// assert_eq!(try_get_approx_jenks_classification(2, &data), Err(ClassifyError::NonFiniteValue(1)));
+/// ```
+pub fn try_get_approx_jenks_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    ApproxJenks {
+        num_bins,
+        options: JenksOptions::default(),
+    }
+    .classify(data)
+}
+
+/// Returns a Classification object following an approximation of the Jenks Natural Breaks algorithm given the desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_approx_jenks_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    ApproxJenks {
+        num_bins,
+        options: JenksOptions::default(),
+    }
+    .classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks approximating the Jenks Natural Breaks algorithm given the desired number of bins and a dataset
+///
+/// Random sets of breaks are sampled and the set with the highest goodness of variance fit (GVF) is kept, so the result is not guaranteed to be optimal
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * The result is not guaranteed to be optimal; use get_jenks_breaks for exact natural breaks
+/// * Panics on empty data, non-finite data or zero bins; use try_get_approx_jenks_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_approx_jenks_breaks;
+///
+/// let data: Vec<i8> = vec![1, 2, 4, 5, 7, 8];
+/// let num_bins = 3;
+///
+/// let result: Vec<f64> = get_approx_jenks_breaks(num_bins, &data);
+///
+/// assert_eq!(result, vec![4.0, 7.0]);
+/// ```
+pub fn get_approx_jenks_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64> {
+    try_get_approx_jenks_breaks(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks approximating the Jenks Natural Breaks algorithm given the desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_approx_jenks_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(try_get_approx_jenks_breaks(0, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_approx_jenks_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    try_get_approx_jenks_breaks_observed(num_bins, data, &mut |_: &Event| {})
+}
+
+/// Returns a vector of breaks approximating the Jenks Natural Breaks algorithm given the desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_approx_jenks_breaks, try_get_approx_jenks_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_approx_jenks_breaks_with_policy(3, &data, &policy), try_get_approx_jenks_breaks(3, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_approx_jenks_breaks_with_policy(3, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_approx_jenks_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    ApproxJenks {
+        num_bins,
+        options: JenksOptions::default(),
+    }
+    .breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks approximating the Jenks Natural Breaks algorithm given the desired number of bins and a dataset, reporting every permutation and improvement in GVF to an Observer
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `observer` - A mutable reference to an Observer that receives diagnostic events
+pub fn try_get_approx_jenks_breaks_observed<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    observer: &mut dyn Observer,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    try_get_approx_jenks_breaks_sorted(
+        num_bins,
+        &JenksOptions::default(),
+        &SortedData::try_new_without_sums(data)?,
+        observer,
+    )
+}
+
+/// Returns the breaks found by the approximate Jenks search along with their GVF and the number of permutations tried, given the desired number of bins, a dataset and the JenksOptions of the search, or a ClassifyError if the input is invalid
+///
+/// Random sets of breaks are drawn from an RNG seeded with `options.seed`, so the same options always produce the same result
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `options` - A reference to the JenksOptions deciding the seed, budget, restarts and early stopping of the search
+///
+/// # Edge Cases
+///
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * JenksBudget::Time relies on the system clock, which is unavailable on wasm32-unknown-unknown
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_approx_jenks_search, JenksBudget, JenksOptions};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+/// let options = JenksOptions {
+///     budget: JenksBudget::Permutations(100),
+///     restarts: 2,
+///     ..Default::default()
+/// };
+///
+/// let result = try_get_approx_jenks_search(3, &data, &options).unwrap();
+///
+/// assert_eq!(result.breaks, vec![4.0, 7.0]);
+/// assert!((result.gvf - 0.96).abs() < 1e-9);
+/// assert_eq!(result.permutations, 200);
+/// ```
+pub fn try_get_approx_jenks_search<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    options: &JenksOptions,
+) -> Result<JenksSearch, ClassifyError> {
+    let mut pseudo_rng = StdRng::seed_from_u64(options.seed);
+    try_get_approx_jenks_search_with_rng(num_bins, data, options, &mut pseudo_rng)
+}
+
+/// Returns the breaks found by the approximate Jenks search along with their GVF and the number of permutations tried, given the desired number of bins, a dataset, the JenksOptions of the search and the RNG drawing the random sets of breaks, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `options` - A reference to the JenksOptions deciding the budget, restarts and early stopping of the search (its seed is ignored)
+/// * `rng` - A mutable reference to any random number generator (RNG) implementing rand's RngCore
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_approx_jenks_search_with_rng, JenksOptions};
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+/// let mut rng = StdRng::seed_from_u64(42);
+///
+/// let result = try_get_approx_jenks_search_with_rng(3, &data, &JenksOptions::default(), &mut rng).unwrap();
+///
+/// assert_eq!(result.breaks, vec![4.0, 7.0]);
+/// ```
+pub fn try_get_approx_jenks_search_with_rng<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    options: &JenksOptions,
+    rng: &mut dyn RngCore,
+) -> Result<JenksSearch, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    validate_jenks_options(options)?;
+
+    try_get_approx_jenks_search_sorted(
+        num_bins,
+        options,
+        &SortedData::try_new_without_sums(data)?,
+        rng,
+        &mut |_: &Event| {},
+    )
+}
+
+/// Returns a vector of breaks approximating the Jenks Natural Breaks algorithm given the desired number of bins, the search options and a SortedData object, reporting every permutation and improvement in GVF to an Observer, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_approx_jenks_breaks_sorted(
+    num_bins: usize,
+    options: &JenksOptions,
+    sorted: &SortedData,
+    observer: &mut dyn Observer,
+) -> Result<Vec<f64>, ClassifyError> {
+    let mut pseudo_rng = StdRng::seed_from_u64(options.seed);
+    Ok(
+        try_get_approx_jenks_search_sorted(num_bins, options, sorted, &mut pseudo_rng, observer)?
+            .breaks,
+    )
+}
+
+/// Returns the outcome of the approximate Jenks search given the desired number of bins, the search options, a SortedData object and an RNG, reporting every permutation and improvement in GVF to an Observer, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_approx_jenks_search_sorted(
+    num_bins: usize,
+    options: &JenksOptions,
+    sorted: &SortedData,
+    rng: &mut dyn RngCore,
+    observer: &mut dyn Observer,
+) -> Result<JenksSearch, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    validate_jenks_options(options)?;
+    let unique_val_map = sorted.unique_vals();
 
     let num_unique_vals = unique_val_map.len();
     let true_num_bins = std::cmp::min(num_unique_vals, num_bins);
 
-    let gssd = calc_gssd(&sorted_data);
+    let gssd = calc_gssd(sorted.values());
+    let centered_sums = sorted.centered_sums();
+    let score = |unique_breaks: &[usize]| -> f64 {
+        let mut breaks: Vec<usize> = vec![];
+        unique_to_normal_breaks(unique_breaks, unique_val_map, &mut breaks);
+        calc_gvf_from_sums(&breaks, &centered_sums, gssd)
+    };
+
+    let (best_breaks, gvf, permutations) = search_rand_breaks(
+        num_unique_vals,
+        true_num_bins - 1,
+        sorted.num_vals(),
+        &score,
+        options,
+        rng,
+        observer,
+    );
+
+    Ok(JenksSearch {
+        breaks: best_breaks
+            .iter()
+            .map(|item| unique_val_map[*item].val)
+            .collect(),
+        gvf,
+        permutations,
+    })
+}
+
+/// Returns a vector of breaks approximating the Jenks Natural Breaks algorithm given the desired number of bins, the search options and a weighted dataset, maximizing the weighted GVF over random sets of breaks, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_approx_jenks_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    num_bins: usize,
+    options: &JenksOptions,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    validate_jenks_options(options)?;
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+
+    let num_unique_vals = weighted_vals.len();
+    let true_num_bins = std::cmp::min(num_unique_vals, num_bins);
+
+    let gssd = calc_weighted_gssd(&weighted_vals);
+    let prefix_sums = PrefixSums::from_weighted(&weighted_vals);
+    let score = |breaks: &[usize]| -> f64 { calc_gvf_from_sums(breaks, &prefix_sums, gssd) };
 
-    let mut rand_breaks: Vec<usize> = vec![0_usize; true_num_bins - 1];
-    let mut best_breaks: Vec<usize> = vec![0_usize; true_num_bins - 1];
-    let mut unique_rand_breaks: Vec<usize> = vec![0_usize; true_num_bins - 1];
+    let mut pseudo_rng = StdRng::seed_from_u64(options.seed);
+    let (best_breaks, _, _) = search_rand_breaks(
+        num_unique_vals,
+        true_num_bins - 1,
+        data.len(),
+        &score,
+        options,
+        &mut pseudo_rng,
+        &mut |_: &Event| {},
+    );
 
+    Ok(best_breaks
+        .iter()
+        .map(|item| weighted_vals[*item].val)
+        .collect())
+}
+
+/// Number of random sets of breaks drawn and scored together during the approximate Jenks search
+const JENKS_SEARCH_BATCH_SIZE: usize = 256;
+
+/// Returns the set of unique value indices with the highest GVF found by the approximate Jenks search, along with its GVF and the total number of sets of breaks tried
+///
+/// # Arguments
+///
+/// * `num_unique_vals` - The number of unique values from which breaks are drawn
+/// * `num_breaks` - The number of breaks in every set
+/// * `num_vals` - The number of data points, which sets the number of permutations of JenksBudget::Auto
+/// * `score` - A function returning the GVF of a set of unique value indices
+/// * `options` - A reference to the (validated) JenksOptions of the search
+/// * `rng` - A mutable reference to the random number generator (RNG) drawing the breaks of the first restart and seeding the RNGs of the others
+/// * `observer` - A mutable reference to an Observer that receives diagnostic events
+fn search_rand_breaks(
+    num_unique_vals: usize,
+    num_breaks: usize,
+    num_vals: usize,
+    score: &(dyn Fn(&[usize]) -> f64 + Sync),
+    options: &JenksOptions,
+    rng: &mut dyn RngCore,
+    observer: &mut dyn Observer,
+) -> (Vec<usize>, f64, usize) {
+    let permutations = match options.budget {
+        JenksBudget::Auto => Some((5000 * 2200 * 4 / num_vals).clamp(10, 10000)),
+        JenksBudget::Permutations(permutations) => Some(permutations),
+        JenksBudget::Time(_) => None,
+    };
+    // The clock is only read for time budgets, as it isn't available on every target
+    let time_budget = match options.budget {
+        JenksBudget::Time(duration) => Some((Instant::now(), duration)),
+        _ => None,
+    };
+
+    let mut rand_breaks: Vec<usize> = vec![0_usize; num_breaks];
+    let mut best_breaks: Vec<usize> = vec![0_usize; num_breaks];
     let mut max_gvf: f64 = 0.0;
+    let mut index = 0;
 
-    let c = 5000 * 2200 * 4;
-    let mut permutations = c / num_vals;
-    if permutations < 10 {
-        permutations = 10
+    for restart in 0..options.restarts {
+        // A time budget is split evenly between the restarts
+        let deadline = time_budget.map(|(start, duration)| {
+            start + duration.mul_f64((restart + 1) as f64 / options.restarts as f64)
+        });
+        // The first restart draws from the given RNG, so that a single restart reproduces the original search, and every later restart draws from its own RNG seeded from it
+        let mut restart_rng: Option<StdRng> = None;
+        if restart > 0 {
+            let mut seed = <StdRng as SeedableRng>::Seed::default();
+            rng.fill_bytes(&mut seed);
+            restart_rng = Some(StdRng::from_seed(seed));
+        }
+        let restart_rng: &mut dyn RngCore = match restart_rng.as_mut() {
+            Some(restart_rng) => restart_rng,
+            None => &mut *rng,
+        };
+        let mut restart_gvf: f64 = 0.0;
+        let mut num_stalled = 0;
+        let mut num_tried = 0;
+
+        'restart: loop {
+            let batch_size = permutations.map_or(JENKS_SEARCH_BATCH_SIZE, |permutations| {
+                std::cmp::min(permutations - num_tried, JENKS_SEARCH_BATCH_SIZE)
+            });
+            let out_of_time =
+                num_tried > 0 && deadline.map_or(false, |deadline| Instant::now() >= deadline);
+            if batch_size == 0 || out_of_time {
+                break;
+            }
+
+            // Candidates are drawn before being scored so that the RNG sequence (and thus the result) is the same whether or not their GVFs are computed in parallel
+            let mut candidates: Vec<Vec<usize>> = Vec::with_capacity(batch_size);
+            for _ in 0..batch_size {
+                pick_rand_breaks(&mut rand_breaks, &num_unique_vals, restart_rng);
+                candidates.push(rand_breaks.clone());
+            }
+            let gvfs = map_slice(&candidates, |breaks| score(breaks));
+
+            for (candidate, new_gvf) in candidates.iter().zip(gvfs) {
+                observer.on_event(&Event::Permutation {
+                    index,
+                    gvf: new_gvf,
+                });
+                index += 1;
+                num_tried += 1;
+                if new_gvf > restart_gvf + options.tolerance {
+                    num_stalled = 0;
+                } else {
+                    num_stalled += 1;
+                }
+                if new_gvf > restart_gvf {
+                    restart_gvf = new_gvf;
+                }
+                if new_gvf > max_gvf {
+                    max_gvf = new_gvf;
+                    best_breaks.copy_from_slice(candidate);
+                    observer.on_event(&Event::BestGvf(max_gvf));
+                }
+                if options
+                    .patience
+                    .map_or(false, |patience| num_stalled >= patience)
+                {
+                    break 'restart;
+                }
+            }
+        }
+    }
+
+    (best_breaks, max_gvf, index)
+}
+
+/// Returns a ClassifyError if any of the JenksOptions is invalid
+fn validate_jenks_options(options: &JenksOptions) -> Result<(), ClassifyError> {
+    if options.restarts == 0 {
+        return Err(ClassifyError::InvalidParameter(
+            "number of restarts must be at least 1".to_string(),
+        ));
+    }
+    if options.budget == JenksBudget::Permutations(0) {
+        return Err(ClassifyError::InvalidParameter(
+            "number of permutations must be at least 1".to_string(),
+        ));
+    }
+    if !options.tolerance.is_finite() || options.tolerance < 0.0 {
+        return Err(ClassifyError::InvalidParameter(
+            "tolerance must be a non-negative finite number".to_string(),
+        ));
+    }
+    if options.patience == Some(0) {
+        return Err(ClassifyError::InvalidParameter(
+            "patience must be at least 1".to_string(),
+        ));
+    }
+    // Reading the clock panics on wasm32-unknown-unknown, so time budgets can't be honored there
+    if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        if let JenksBudget::Time(_) = options.budget {
+            return Err(ClassifyError::InvalidParameter(
+                "time budgets are not supported on wasm32-unknown-unknown".to_string(),
+            ));
+        }
     }
-    if permutations > 10000 {
-        permutations = 10000
+    Ok(())
+}
+
+/// Represents how many random sets of breaks the approximate Jenks search may try
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JenksBudget {
+    /// 44,000,000 divided by the number of data points, clamped between 10 and 10,000 permutations per restart
+    Auto,
+    /// A fixed number of permutations per restart
+    Permutations(usize),
+    /// As many permutations as fit within a wall-clock duration, split evenly between the restarts (rejected with a ClassifyError on wasm32-unknown-unknown, which has no clock)
+    Time(Duration),
+}
+
+impl Default for JenksBudget {
+    fn default() -> Self {
+        JenksBudget::Auto
     }
-    println!("permutations: {}", permutations);
+}
 
-    let mut pseudo_rng = StdRng::seed_from_u64(123456789);
+/// Represents the settings of the approximate Jenks search
+///
+/// A restart ends once its budget is spent or, if `patience` is set, once that many consecutive permutations have failed to improve the best GVF of the restart by more than `tolerance`; the best breaks across all restarts are kept
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JenksOptions {
+    /// The seed of the random number generator (123456789 by default)
+    pub seed: u64,
+    /// The number of permutations (or time) each restart may use (JenksBudget::Auto by default)
+    pub budget: JenksBudget,
+    /// The number of independent searches, each drawing from its own random number generator seeded from the main one and keeping its own early-stopping count (1 by default)
+    pub restarts: usize,
+    /// The smallest improvement in GVF that resets the early-stopping count (0 by default)
+    pub tolerance: f64,
+    /// The number of consecutive permutations without sufficient improvement after which a restart ends (never by default)
+    pub patience: Option<usize>,
+}
 
-    for _ in 0..permutations {
-        pick_rand_breaks(&mut unique_rand_breaks, &num_unique_vals, &mut pseudo_rng);
-        unique_to_normal_breaks(&unique_rand_breaks, &unique_val_map, &mut rand_breaks);
-        let new_gvf: f64 = calc_gvf(&rand_breaks, &sorted_data, &gssd);
-        if new_gvf > max_gvf {
-            max_gvf = new_gvf;
-            best_breaks[..rand_breaks.len()].copy_from_slice(&rand_breaks[..]);
+impl Default for JenksOptions {
+    fn default() -> Self {
+        JenksOptions {
+            seed: 123456789,
+            budget: JenksBudget::Auto,
+            restarts: 1,
+            tolerance: 0.0,
+            patience: None,
         }
     }
+}
 
-    let mut nat_breaks: Vec<f64> = vec![];
-    nat_breaks.resize(best_breaks.len(), 0.0);
-    for i in 0..best_breaks.len() {
-        nat_breaks[i] = sorted_data[best_breaks[i]];
+/// Represents the outcome of the approximate Jenks search
+#[derive(Debug, Clone, PartialEq)]
+pub struct JenksSearch {
+    /// The breaks with the highest goodness of variance fit (GVF) found
+    pub breaks: Vec<f64>,
+    /// The GVF achieved by the breaks
+    pub gvf: f64,
+    /// The total number of random sets of breaks tried across all restarts
+    pub permutations: usize,
+}
+
+/// Represents the initial breaks from which the Jenks-Caspall algorithm starts refining
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JenksCaspallStart {
+    /// Start from Quantile breaks
+    Quantile,
+    /// Start from Equal Interval breaks
+    EqualInterval,
+}
+
+/// Represents the outcome of the Jenks-Caspall algorithm
+#[derive(Debug, Clone, PartialEq)]
+pub struct JenksCaspallRefinement {
+    /// The converged breaks
+    pub breaks: Vec<f64>,
+    /// The number of iterations (passes over the class boundaries) that moved at least one observation
+    pub iterations: usize,
+    /// The goodness of variance fit (GVF) of the initial breaks followed by the GVF after every iteration
+    pub gvf_trajectory: Vec<f64>,
+}
+
+/// Returns a Classification object following the Jenks-Caspall algorithm given the desired number of bins, the initial breaks and one-dimensional data
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `start` - The JenksCaspallStart deciding the initial breaks
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * Classes left empty by the initial breaks are dropped, so fewer than num_bins bins may be produced
+/// * Panics on empty data, non-finite data or zero bins; use try_get_jenks_caspall_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::{get_jenks_caspall_classification, JenksCaspallStart};
+/// use classify::{Classification, Bin};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 10.0, 11.0, 12.0, 13.0, 30.0, 31.0];
+///
+/// let result: Classification = get_jenks_caspall_classification(3, JenksCaspallStart::Quantile, &data);
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(1.0, 10.0, 3),
+///     Bin::new(10.0, 30.0, 4),
+///     Bin::new(30.0, 31.0, 2)
+/// ];
+///
+/// assert!(result == expected);
+/// ```
+pub fn get_jenks_caspall_classification<T: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+) -> Classification {
+    try_get_jenks_caspall_classification(num_bins, start, data)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Jenks-Caspall algorithm given the desired number of bins, the initial breaks and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `start` - The JenksCaspallStart deciding the initial breaks
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_jenks_caspall_classification, JenksCaspallStart};
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0];
+///
+/// assert_eq!(try_get_jenks_caspall_classification(0, JenksCaspallStart::Quantile, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_jenks_caspall_classification<T: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    JenksCaspall { num_bins, start }.classify(data)
+}
+
+/// Returns a Classification object following the Jenks-Caspall algorithm given the desired number of bins, the initial breaks and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `start` - The JenksCaspallStart deciding the initial breaks
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_jenks_caspall_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    JenksCaspall { num_bins, start }.classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Jenks-Caspall algorithm given the desired number of bins, the initial breaks and a dataset
+///
+/// Starting from the initial breaks, the observations at the boundaries between neighbouring classes are moved from one class to the other for as long as this lowers the total sum of squared deviations around class means
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `start` - The JenksCaspallStart deciding the initial breaks
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * Classes left empty by the initial breaks are dropped, so fewer than num_bins bins may be produced
+/// * The result is a local optimum; use get_jenks_breaks for exact natural breaks
+/// * Panics on empty data, non-finite data or zero bins; use try_get_jenks_caspall_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::{get_jenks_caspall_breaks, JenksCaspallStart};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 10.0, 11.0, 12.0, 13.0, 30.0, 31.0];
+///
+/// let result: Vec<f64> = get_jenks_caspall_breaks(3, JenksCaspallStart::EqualInterval, &data);
+///
+/// assert_eq!(result, vec![10.0, 30.0]);
+/// ```
+pub fn get_jenks_caspall_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+) -> Vec<f64> {
+    try_get_jenks_caspall_breaks(num_bins, start, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Jenks-Caspall algorithm given the desired number of bins, the initial breaks and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `start` - The JenksCaspallStart deciding the initial breaks
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_jenks_caspall_breaks, JenksCaspallStart};
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(try_get_jenks_caspall_breaks(3, JenksCaspallStart::Quantile, &data), Ok(vec![4.0, 7.0]));
+/// assert_eq!(try_get_jenks_caspall_breaks(3, JenksCaspallStart::Quantile, &[f64::NAN]), Err(ClassifyError::NonFiniteValue(0)));
+/// ```
+pub fn try_get_jenks_caspall_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    Ok(try_get_jenks_caspall_refinement(num_bins, start, data)?.breaks)
+}
+
+/// Returns a vector of breaks generated through the Jenks-Caspall algorithm given the desired number of bins, the initial breaks and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `start` - The JenksCaspallStart deciding the initial breaks
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_jenks_caspall_breaks, try_get_jenks_caspall_breaks_with_policy, JenksCaspallStart, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_jenks_caspall_breaks_with_policy(3, JenksCaspallStart::Quantile, &data, &policy), try_get_jenks_caspall_breaks(3, JenksCaspallStart::Quantile, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_jenks_caspall_breaks_with_policy(3, JenksCaspallStart::Quantile, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_jenks_caspall_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    JenksCaspall { num_bins, start }.breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Jenks-Caspall algorithm given the desired number of bins, the initial breaks and a dataset, reporting the GVF of the initial breaks and every iteration along with its GVF to an Observer as it runs
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `start` - The JenksCaspallStart deciding the initial breaks
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `observer` - A mutable reference to an Observer that receives diagnostic events
+pub fn try_get_jenks_caspall_breaks_observed<T: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+    observer: &mut dyn Observer,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    Ok(try_get_jenks_caspall_refinement_sorted(
+        num_bins,
+        start,
+        &SortedData::try_new_without_sums(data)?,
+        observer,
+    )?
+    .breaks)
+}
+
+/// Returns the converged breaks of the Jenks-Caspall algorithm along with the number of iterations and the GVF trajectory, given the desired number of bins, the initial breaks and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `start` - The JenksCaspallStart deciding the initial breaks
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * The GVF trajectory is empty if fewer than two bins are produced
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_jenks_caspall_refinement, JenksCaspallStart};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 10.0, 11.0, 12.0, 13.0, 30.0, 31.0];
+///
+/// let result = try_get_jenks_caspall_refinement(3, JenksCaspallStart::Quantile, &data).unwrap();
+///
+/// assert_eq!(result.breaks, vec![10.0, 30.0]);
+/// assert_eq!(result.iterations, 1);
+/// assert_eq!(result.gvf_trajectory.len(), 2);
+/// assert!(result.gvf_trajectory[1] > result.gvf_trajectory[0]);
+/// ```
+pub fn try_get_jenks_caspall_refinement<T: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+) -> Result<JenksCaspallRefinement, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    try_get_jenks_caspall_refinement_sorted(
+        num_bins,
+        start,
+        &SortedData::try_new_without_sums(data)?,
+        &mut |_: &Event| {},
+    )
+}
+
+/// Returns the converged breaks of the Jenks-Caspall algorithm along with the number of iterations and the GVF trajectory, given the desired number of bins, the initial breaks and a SortedData object, reporting every iteration and its GVF to an Observer, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_jenks_caspall_refinement_sorted(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    sorted: &SortedData,
+    observer: &mut dyn Observer,
+) -> Result<JenksCaspallRefinement, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    let true_num_bins = std::cmp::min(sorted.unique_vals().len(), num_bins);
+    let initial_breaks: Vec<f64> = match start {
+        JenksCaspallStart::Quantile => {
+            try_get_quantile_breaks_sorted(true_num_bins, QuantileDefinition::Midpoint, sorted)?
+        }
+        JenksCaspallStart::EqualInterval => {
+            try_get_equal_interval_breaks_sorted(true_num_bins, sorted)?
+        }
+    };
+
+    Ok(refine_jenks_caspall(
+        &unique_to_weighted_vals(sorted.unique_vals()),
+        &initial_breaks,
+        calc_gssd(sorted.values()),
+        observer,
+    ))
+}
+
+/// Returns a vector of breaks generated through the Jenks-Caspall algorithm given the desired number of bins, the initial breaks and a weighted dataset, starting from weighted Quantile breaks if requested and moving weighted values between classes, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_jenks_caspall_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    num_bins: usize,
+    start: JenksCaspallStart,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
     }
-    println!("Breaks: {:#?}", nat_breaks);
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+
+    let true_num_bins = std::cmp::min(weighted_vals.len(), num_bins);
+    let initial_breaks: Vec<f64> = match start {
+        JenksCaspallStart::Quantile => calc_weighted_quantile_breaks(
+            &weighted_vals,
+            true_num_bins,
+            QuantileDefinition::Midpoint,
+        ),
+        JenksCaspallStart::EqualInterval => try_get_equal_interval_breaks(
+            true_num_bins,
+            &[
+                weighted_vals[0].val,
+                weighted_vals[weighted_vals.len() - 1].val,
+            ],
+        )?,
+    };
 
-    nat_breaks
+    Ok(refine_jenks_caspall(
+        &weighted_vals,
+        &initial_breaks,
+        calc_weighted_gssd(&weighted_vals),
+        &mut |_: &Event| {},
+    )
+    .breaks)
+}
+
+/// Refines a set of initial breaks through the Jenks-Caspall algorithm given weighted unique values and their global sum of squared deviations, moving unique values between neighbouring classes until no move lowers the total SSD
+///
+/// The GVF of the initial breaks is reported to the Observer, followed by an `Event::Iteration` and the new GVF after every iteration
+fn refine_jenks_caspall(
+    unique_val_map: &[WeightedVal],
+    initial_breaks: &[f64],
+    gssd: f64,
+    observer: &mut dyn Observer,
+) -> JenksCaspallRefinement {
+    // Class boundaries are kept as indices into the unique value map (the first unique value of
+    // every class but the first), dropping any boundary that would leave a class empty
+    let mut unique_breaks: Vec<usize> = vec![];
+    for item in initial_breaks {
+        let idx = unique_val_map.partition_point(|u_val| u_val.val < *item);
+        if idx > *unique_breaks.last().unwrap_or(&0) && idx < unique_val_map.len() {
+            unique_breaks.push(idx);
+        }
+    }
+    if unique_breaks.is_empty() {
+        return JenksCaspallRefinement {
+            breaks: vec![],
+            iterations: 0,
+            gvf_trajectory: vec![],
+        };
+    }
+
+    // Class SSDs come from prefix sums centered on the median, so that data far from zero doesn't lose precision to cancellation
+    let prefix_sums = PrefixSums::from_weighted(unique_val_map);
+    // Moves must lower the total SSD by more than this to count, so rounding can't cause cycles
+    let tolerance = gssd * 1e-12;
+
+    let mut gvf_trajectory: Vec<f64> = vec![calc_gvf_from_sums(&unique_breaks, &prefix_sums, gssd)];
+    observer.on_event(&Event::BestGvf(gvf_trajectory[0]));
+    let mut iterations = 0;
+    loop {
+        let mut moved = false;
+        for j in 0..unique_breaks.len() {
+            let upper_first = unique_breaks[j];
+            let lower_first = if j == 0 { 0 } else { unique_breaks[j - 1] };
+            let upper_end = match unique_breaks.get(j + 1) {
+                Some(next) => *next,
+                None => unique_val_map.len(),
+            };
+            let pair_ssd = |boundary: usize| {
+                prefix_sums.ssd(lower_first, boundary) + prefix_sums.ssd(boundary, upper_end)
+            };
+            let current = pair_ssd(upper_first);
+
+            // Try moving the last unique value of the lower class up, then the first unique value of the upper class down
+            if upper_first - 1 > lower_first && pair_ssd(upper_first - 1) < current - tolerance {
+                unique_breaks[j] -= 1;
+                moved = true;
+            } else if upper_end - 1 > upper_first && pair_ssd(upper_first + 1) < current - tolerance
+            {
+                unique_breaks[j] += 1;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+        observer.on_event(&Event::Iteration(iterations));
+        iterations += 1;
+        let gvf = calc_gvf_from_sums(&unique_breaks, &prefix_sums, gssd);
+        observer.on_event(&Event::BestGvf(gvf));
+        gvf_trajectory.push(gvf);
+    }
+
+    let mut breaks: Vec<f64> = vec![];
+    for item in unique_breaks {
+        breaks.push(unique_val_map[item].val);
+    }
+
+    JenksCaspallRefinement {
+        breaks,
+        iterations,
+        gvf_trajectory,
+    }
+}
+
+/// Returns the optimal breaks (as indices into the unique value map) that minimize the total within-class sum of squared deviations, using Fisher's dynamic programming algorithm
+///
+/// Each unique value is weighted by its number of occurrences, so repeated values are never split between two bins
+///
+/// # Arguments
+///
+/// * `u_val_map` - A reference to a map of unique values to their first and last occurrences in the dataset
+/// * `num_bins` - The desired number of bins (must be between 1 and the number of unique values)
+pub fn calc_fisher_jenks_breaks(u_val_map: &[UniqueVal], num_bins: usize) -> Vec<usize> {
+    calc_weighted_fisher_jenks_breaks(&unique_to_weighted_vals(u_val_map), num_bins)
+}
+
+/// Returns the optimal breaks (as indices into the weighted unique values) that minimize the total weighted within-class sum of squared deviations, using Fisher's dynamic programming algorithm
+///
+/// # Arguments
+///
+/// * `u_val_map` - A reference to a collection of sorted unique values along with their total weights
+/// * `num_bins` - The desired number of bins (must be between 1 and the number of unique values)
+pub fn calc_weighted_fisher_jenks_breaks(u_val_map: &[WeightedVal], num_bins: usize) -> Vec<usize> {
+    let num_unique_vals = u_val_map.len();
+
+    // costs[j][i] holds the minimum SSD of splitting unique values 0..=i into j + 1 bins, and
+    // starts[j][i] holds the index of the first unique value in the last of those bins
+    let mut costs: Vec<Vec<f64>> = vec![vec![0.0; num_unique_vals]; num_bins];
+    let mut starts: Vec<Vec<usize>> = vec![vec![0; num_unique_vals]; num_bins];
+
+    // Class costs come from prefix sums centered on the median, so that data far from zero doesn't lose precision to cancellation
+    let prefix_sums = PrefixSums::from_weighted(u_val_map);
+    for (i, cost) in costs[0].iter_mut().enumerate() {
+        *cost = prefix_sums.ssd(0, i + 1);
+    }
+
+    for j in 1..num_bins {
+        for i in j..num_unique_vals {
+            let mut best_cost = f64::INFINITY;
+            let mut best_start = j;
+            for start in (j..=i).rev() {
+                let cost = prefix_sums.ssd(start, i + 1) + costs[j - 1][start - 1];
+                if cost < best_cost {
+                    best_cost = cost;
+                    best_start = start;
+                }
+            }
+            costs[j][i] = best_cost;
+            starts[j][i] = best_start;
+        }
+    }
+
+    let mut breaks: Vec<usize> = vec![0; num_bins - 1];
+    let mut last = num_unique_vals - 1;
+    for j in (1..num_bins).rev() {
+        breaks[j - 1] = starts[j][last];
+        last = starts[j][last] - 1;
+    }
+
+    breaks
 }
 
 /// Populates a vector with a set of breaks as unique random integers that are valid indices within the dataset given the number of data points and an RNG
@@ -135,8 +1311,8 @@ pub fn get_jenks_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64>
 ///
 /// * `breaks` - A mutable reference to an empty vector of breaks whose length is taken to be the desired number of breaks
 /// * `num_vals` - A reference to the number of data points
-/// * `rng` - A mutable reference to a seedable random number generator (RNG) from the "rand" crate
-pub fn pick_rand_breaks(breaks: &mut Vec<usize>, num_vals: &usize, rng: &mut StdRng) {
+/// * `rng` - A mutable reference to a random number generator (RNG) from the "rand" crate
+pub fn pick_rand_breaks<R: Rng + ?Sized>(breaks: &mut [usize], num_vals: &usize, rng: &mut R) {
     let num_breaks = breaks.len();
     if num_breaks > num_vals - 1 {
         return;
@@ -160,7 +1336,7 @@ pub fn pick_rand_breaks(breaks: &mut Vec<usize>, num_vals: &usize, rng: &mut Std
 /// * `breaks` - A reference to a vector (usize) of break indices (sorted, ascending)
 /// * `vals` - A reference to a vector (f64) of data points (sorted, ascending)
 /// * `gssd` - A reference to the global sum of squared deviations (GSSD)
-pub fn calc_gvf(breaks: &Vec<usize>, vals: &Vec<f64>, gssd: &f64) -> f64 {
+pub fn calc_gvf(breaks: &[usize], vals: &[f64], gssd: &f64) -> f64 {
     let num_vals = vals.len();
     let num_bins = breaks.len() + 1;
     let mut tssd: f64 = 0.0;
@@ -191,7 +1367,7 @@ pub fn calc_gvf(breaks: &Vec<usize>, vals: &Vec<f64>, gssd: &f64) -> f64 {
 /// # Arguments
 ///
 /// * `data` - A reference to a vector (f64) of data points (sorted, ascending)
-pub fn calc_gssd(data: &Vec<f64>) -> f64 {
+pub fn calc_gssd(data: &[f64]) -> f64 {
     let num_vals = data.len();
     let mut mean = 0.0;
     let mut max_val: f64 = data[0];
@@ -212,3 +1388,44 @@ pub fn calc_gssd(data: &Vec<f64>) -> f64 {
 
     gssd
 }
+
+/// Calculates goodness of variance fit (GVF) for a particular set of breaks in O(k) time, where k is the number of bins, using prefix sums
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a vector (usize) of break indices into the points the prefix sums were taken over (sorted, ascending)
+/// * `prefix_sums` - A reference to the prefix sums of the sorted data points or weighted unique values
+/// * `gssd` - The global sum of squared deviations (GSSD)
+fn calc_gvf_from_sums(breaks: &[usize], prefix_sums: &PrefixSums, gssd: f64) -> f64 {
+    let mut tssd: f64 = 0.0;
+    let mut lower = 0;
+    for upper in breaks.iter().copied().chain(Some(prefix_sums.num_points())) {
+        tssd += prefix_sums.ssd(lower, upper);
+        lower = upper;
+    }
+    1.0 - (tssd / gssd)
+}
+
+/// Calculates the weighted global sum of squared deviations (GSSD) of weighted unique values (0 if there are none)
+///
+/// # Arguments
+///
+/// * `weighted_vals` - A reference to a collection of unique values along with their total weights
+fn calc_weighted_gssd(weighted_vals: &[WeightedVal]) -> f64 {
+    let mut weight = 0.0;
+    let mut mean = 0.0;
+    for item in weighted_vals {
+        weight += item.weight;
+        mean += item.val * item.weight;
+    }
+    if weight == 0.0 {
+        return 0.0;
+    }
+    mean /= weight;
+
+    let mut gssd: f64 = 0.0;
+    for item in weighted_vals {
+        gssd += (item.val - mean) * (item.val - mean) * item.weight;
+    }
+    gssd
+}
@@ -1,24 +1,150 @@
 mod jenks;
+pub use jenks::{get_approx_jenks_breaks, get_approx_jenks_classification};
 pub use jenks::{get_jenks_breaks, get_jenks_classification};
+pub use jenks::{get_jenks_caspall_breaks, get_jenks_caspall_classification};
+pub use jenks::{try_get_approx_jenks_breaks, try_get_approx_jenks_classification};
+pub use jenks::{
+    try_get_approx_jenks_breaks_with_policy, try_get_approx_jenks_classification_with_policy,
+};
+pub use jenks::{try_get_approx_jenks_search, try_get_approx_jenks_search_with_rng};
+pub use jenks::{try_get_jenks_breaks, try_get_jenks_classification};
+pub use jenks::{try_get_jenks_breaks_with_policy, try_get_jenks_classification_with_policy};
+pub use jenks::{try_get_jenks_caspall_breaks, try_get_jenks_caspall_classification};
+pub use jenks::{
+    try_get_jenks_caspall_breaks_with_policy, try_get_jenks_caspall_classification_with_policy,
+};
+pub use jenks::{try_get_jenks_caspall_refinement, JenksCaspallRefinement, JenksCaspallStart};
+pub use jenks::{JenksBudget, JenksOptions, JenksSearch};
+
+mod ckmeans;
+pub use ckmeans::{get_ckmeans_breaks, get_ckmeans_classification};
+pub use ckmeans::{try_get_ckmeans_breaks, try_get_ckmeans_classification};
+pub use ckmeans::{try_get_ckmeans_breaks_with_policy, try_get_ckmeans_classification_with_policy};
+pub use ckmeans::{try_get_ckmeans_clustering, CkmeansClustering};
+
+mod maximum_breaks;
+pub use maximum_breaks::try_get_maximum_breaks_with_min_class_size;
+pub use maximum_breaks::{get_maximum_breaks, get_maximum_breaks_classification};
+pub use maximum_breaks::{try_get_maximum_breaks, try_get_maximum_breaks_classification};
+pub use maximum_breaks::{
+    try_get_maximum_breaks_classification_with_policy, try_get_maximum_breaks_with_policy,
+};
+
+mod geometric_interval;
+pub use geometric_interval::{
+    get_geometric_interval_breaks, get_geometric_interval_classification,
+};
+pub use geometric_interval::{
+    try_get_geometric_interval_breaks, try_get_geometric_interval_classification,
+};
+pub use geometric_interval::{
+    try_get_geometric_interval_breaks_with_policy,
+    try_get_geometric_interval_classification_with_policy,
+};
+
+mod pretty;
+pub use pretty::{get_pretty_breaks, get_pretty_classification};
+pub use pretty::{snap_breaks_to_nice, try_snap_breaks_to_nice, SnappedBreaks};
+pub use pretty::{try_get_pretty_breaks, try_get_pretty_classification};
+pub use pretty::{try_get_pretty_breaks_with_policy, try_get_pretty_classification_with_policy};
 
 mod quantile;
+pub use quantile::try_get_quantile_breaks_with_definition;
 pub use quantile::{get_quantile_breaks, get_quantile_classification};
+pub use quantile::{try_get_quantile_breaks, try_get_quantile_classification};
+pub use quantile::{
+    try_get_quantile_breaks_with_policy, try_get_quantile_classification_with_policy,
+};
+
+mod percentiles;
+pub use percentiles::try_get_percentile_breaks_with_definition;
+pub use percentiles::{get_percentile_breaks, get_percentile_classification};
+pub use percentiles::{try_get_percentile_breaks, try_get_percentile_classification};
+pub use percentiles::{
+    try_get_percentile_breaks_with_policy, try_get_percentile_classification_with_policy,
+};
+pub use percentiles::{try_get_percentiles, QuantileDefinition};
 
 mod head_tail;
 pub use head_tail::{get_head_tail_breaks, get_head_tail_classification};
+pub use head_tail::{try_get_head_tail_breaks, try_get_head_tail_classification};
+pub use head_tail::{
+    try_get_head_tail_breaks_with_policy, try_get_head_tail_classification_with_policy,
+};
 
 mod equal_interval;
 pub use equal_interval::{get_equal_interval_breaks, get_equal_interval_classification};
+pub use equal_interval::{try_get_equal_interval_breaks, try_get_equal_interval_classification};
+pub use equal_interval::{
+    try_get_equal_interval_breaks_with_policy, try_get_equal_interval_classification_with_policy,
+};
+
+mod defined_interval;
+pub use defined_interval::try_get_defined_interval_breaks_with_options;
+pub use defined_interval::{get_defined_interval_breaks, get_defined_interval_classification};
+pub use defined_interval::{
+    try_get_defined_interval_breaks, try_get_defined_interval_classification,
+};
+pub use defined_interval::{
+    try_get_defined_interval_breaks_with_policy,
+    try_get_defined_interval_classification_with_policy,
+};
 
 mod standard_deviation;
 pub use standard_deviation::{get_st_dev_breaks, get_st_dev_classification};
+pub use standard_deviation::{try_get_st_dev_breaks, try_get_st_dev_classification};
+pub use standard_deviation::{
+    try_get_st_dev_breaks_with_policy, try_get_st_dev_classification_with_policy,
+};
 
 mod hinge;
 pub use hinge::{get_hinge_breaks, get_hinge_classification};
+pub use hinge::{try_get_hinge_breaks, try_get_hinge_classification};
+pub use hinge::{try_get_hinge_breaks_with_policy, try_get_hinge_classification_with_policy};
+
+mod user_defined;
+pub use user_defined::{get_user_defined_breaks, get_user_defined_classification};
+pub use user_defined::{try_get_user_defined_breaks, try_get_user_defined_classification};
+pub use user_defined::{
+    try_get_user_defined_breaks_with_policy, try_get_user_defined_classification_with_policy,
+};
+
+mod auto;
+pub use auto::{select_num_bins, AutoSelection, AutoStrategy};
+
+mod classifier;
+pub use classifier::{ApproxJenks, Ckmeans, EqualInterval, HeadTail, Hinge, Jenks, JenksCaspall};
+pub use classifier::{ClassificationMethod, Classifier, DefinedInterval};
+pub use classifier::{GeometricInterval, MaximumBreaks, Percentiles, Pretty, Quantile};
+pub use classifier::{StdDev, UserDefined};
+
+mod diagnostics;
+pub use diagnostics::{Event, Observer, Report};
+
+mod error;
+pub use error::ClassifyError;
+
+pub mod metrics;
+
+mod missing;
+pub use missing::{apply_missing_policy, apply_missing_policy_weighted, MissingPolicy};
+
+mod parallel;
+
+mod sketch;
+pub use sketch::QuantileSketch;
+
+mod sorted_data;
+pub use sorted_data::SortedData;
 
 mod utilities;
-pub use utilities::{breaks_to_classification, classify_val};
-pub use utilities::{Bin, Classification};
+pub use utilities::try_breaks_to_classification_weighted;
+pub use utilities::try_breaks_to_classification_with_closure;
+pub use utilities::try_classify_val;
+pub use utilities::try_histogram_to_classification;
+pub use utilities::{breaks_to_classification, classify_val, classify_values};
+pub use utilities::{try_breaks_to_classification, try_breaks_to_classification_with_policy};
+pub use utilities::{Bin, Classification, Closure, GoodnessOfFit, UniqueVal};
 
 #[cfg(target_family = "wasm")]
 mod wasm;
@@ -53,6 +179,50 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_quantile_definitions() {
+        let data: Vec<f64> = vec![10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.0];
+        let definitions = [
+            (QuantileDefinition::Type1, 1.0, 5.0),
+            (QuantileDefinition::Type2, 1.5, 5.5),
+            (QuantileDefinition::Type3, 1.0, 5.0),
+            (QuantileDefinition::Type4, 1.0, 5.0),
+            (QuantileDefinition::Type5, 1.5, 5.5),
+            (QuantileDefinition::Type6, 1.1, 5.5),
+            (QuantileDefinition::Type7, 1.9, 5.5),
+            (QuantileDefinition::Type8, 1.3666666666666667, 5.5),
+            (QuantileDefinition::Type9, 1.4, 5.5),
+            (QuantileDefinition::Midpoint, 1.5, 5.5),
+        ];
+        for (definition, perc_10, perc_50) in definitions {
+            let result = try_get_percentiles(&[10.0, 50.0, 0.0, 100.0], definition, &data).unwrap();
+            assert!((result[0] - perc_10).abs() < 1e-9, "{:?}", definition);
+            assert!((result[1] - perc_50).abs() < 1e-9, "{:?}", definition);
+            assert_eq!(result[2..], [1.0, 10.0], "{:?}", definition);
+        }
+
+        let skewed: Vec<f64> = vec![1.0, 1.0, 1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0, 55.0];
+        for num_bins in 1..12 {
+            assert_eq!(
+                try_get_quantile_breaks_with_definition(
+                    num_bins,
+                    QuantileDefinition::Midpoint,
+                    &skewed
+                ),
+                try_get_quantile_breaks(num_bins, &skewed)
+            );
+        }
+
+        let restored: ClassificationMethod =
+            serde_json::from_str(r#"{"Quantile":{"num_bins":3}}"#).unwrap();
+        assert_eq!(
+            restored.breaks(&skewed),
+            Ok(get_quantile_breaks(3, &skewed))
+        );
+        assert!(try_get_percentiles(&[f64::NAN], QuantileDefinition::Type7, &data).is_err());
+        assert!(try_get_percentile_breaks(&[-1.0], &data).is_err());
+    }
+
     #[test]
     fn test_create_unique_val_mapping() {
         let mut unique_val_map: Vec<UniqueVal> = vec![];
@@ -60,7 +230,7 @@ mod tests {
 
         utilities::create_unique_val_mapping(&mut unique_val_map, &data);
 
-        let expected = vec![
+        let expected = [
             UniqueVal {
                 val: 2.0,
                 first: 0,
@@ -96,6 +266,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_unique_val_mapping_trailing_duplicates() {
+        let mut unique_val_map: Vec<UniqueVal> = vec![];
+        let data: Vec<f64> = vec![1.0, 3.0, 3.0, 3.0];
+
+        utilities::create_unique_val_mapping(&mut unique_val_map, &data);
+
+        assert_eq!(unique_val_map.len(), 2);
+        assert_eq!(unique_val_map[1].first, 1);
+        assert_eq!(
+            unique_val_map[1].last, 3,
+            "create_unique_val_mapping not working!"
+        );
+    }
+
     #[test]
     fn test_unique_to_normal_breaks() {
         let mut unique_val_map: Vec<UniqueVal> = vec![];
@@ -126,6 +311,284 @@ mod tests {
         assert_eq!(breaks, vec![3, 7], "pick_rand_breaks not working!");
     }
 
+    #[test]
+    fn test_calc_fisher_jenks_breaks() {
+        let mut unique_val_map: Vec<UniqueVal> = vec![];
+        let data: Vec<f64> = vec![1.0, 1.0, 1.0, 2.0, 9.0, 10.0, 10.0, 20.0, 21.0];
+        utilities::create_unique_val_mapping(&mut unique_val_map, &data);
+
+        let breaks = jenks::calc_fisher_jenks_breaks(&unique_val_map, 3);
+
+        assert_eq!(breaks, vec![2, 4], "calc_fisher_jenks_breaks not working!");
+    }
+
+    #[test]
+    fn test_jenks_large_offset() {
+        // Timestamps and projected coordinates sit far from zero, where uncentered sums of squares cancel
+        let data: Vec<f64> = [0.0, 0.1, 0.2, 1.0, 1.1, 1.2, 3.0, 3.05, 3.1, 7.0, 7.2]
+            .iter()
+            .map(|val| val + 1e9)
+            .collect();
+        let expected = vec![1e9 + 1.0, 1e9 + 3.0, 1e9 + 7.0];
+
+        assert_eq!(get_ckmeans_breaks(4, &data), expected);
+        assert_eq!(get_jenks_breaks(4, &data), get_ckmeans_breaks(4, &data));
+        assert!(metrics::gvf(&get_jenks_breaks(4, &data), &data).unwrap() > 0.99);
+
+        for start in [
+            JenksCaspallStart::Quantile,
+            JenksCaspallStart::EqualInterval,
+        ] {
+            let unshifted: Vec<f64> = data.iter().map(|val| val - 1e9).collect();
+            let shifted_breaks: Vec<f64> = get_jenks_caspall_breaks(4, start, &unshifted)
+                .iter()
+                .map(|brk| brk + 1e9)
+                .collect();
+            assert_eq!(get_jenks_caspall_breaks(4, start, &data), shifted_breaks);
+        }
+        assert_eq!(
+            get_jenks_caspall_breaks(4, JenksCaspallStart::Quantile, &data),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_jenks_beats_approx_jenks() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut data: Vec<f64> = vec![];
+        for _ in 0..500 {
+            data.push(rng.gen_range(0.0..1000.0));
+        }
+        data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let gssd = jenks::calc_gssd(&data);
+
+        let to_idx = |breaks: Vec<f64>| -> Vec<usize> {
+            breaks
+                .iter()
+                .map(|b| data.iter().position(|v| v == b).unwrap())
+                .collect()
+        };
+        let exact = to_idx(get_jenks_breaks(6, &data));
+        let approx = to_idx(get_approx_jenks_breaks(6, &data));
+
+        assert!(jenks::calc_gvf(&exact, &data, &gssd) >= jenks::calc_gvf(&approx, &data, &gssd));
+    }
+
+    #[test]
+    fn test_jenks_caspall_convergence() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut data: Vec<f64> = vec![];
+        for _ in 0..300 {
+            data.push(rng.gen_range(0.0..100.0_f64).powi(2));
+        }
+        let exact_gvf = metrics::gvf(&get_jenks_breaks(5, &data), &data).unwrap();
+
+        for start in [
+            JenksCaspallStart::Quantile,
+            JenksCaspallStart::EqualInterval,
+        ] {
+            let result = try_get_jenks_caspall_refinement(5, start, &data).unwrap();
+            let trajectory = &result.gvf_trajectory;
+
+            assert_eq!(trajectory.len(), result.iterations + 1);
+            assert!(trajectory.windows(2).all(|pair| pair[1] > pair[0]));
+            assert!(trajectory[result.iterations] <= exact_gvf + 1e-12);
+            assert!(
+                (metrics::gvf(&result.breaks, &data).unwrap() - trajectory[result.iterations])
+                    .abs()
+                    < 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn test_ckmeans_matches_jenks_objective() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut data: Vec<f64> = vec![];
+        for _ in 0..400 {
+            data.push(rng.gen_range(0..60) as f64 * 1.5);
+        }
+
+        for num_bins in 1..8 {
+            let result = try_get_ckmeans_clustering(num_bins, &data).unwrap();
+            let class_sdam = metrics::class_sdam(&result.breaks, &data).unwrap();
+            let jenks_sdam =
+                metrics::class_sdam(&get_jenks_breaks(num_bins, &data), &data).unwrap();
+
+            assert_eq!(result.breaks.len(), num_bins - 1);
+            assert_eq!(result.means.len(), num_bins);
+            for (withinss, ssd) in result.withinss.iter().zip(class_sdam.iter()) {
+                assert!((withinss - ssd).abs() < 1e-6);
+            }
+            assert!(
+                (result.withinss.iter().sum::<f64>() - jenks_sdam.iter().sum::<f64>()).abs() < 1e-6
+            );
+        }
+    }
+
+    #[test]
+    fn test_maximum_breaks_min_class_size() {
+        let data: Vec<f64> = vec![0.0, 0.0, 0.0, 1.0, 1.0, 5.0, 6.0, 20.0, 20.0, 40.0];
+
+        assert_eq!(get_maximum_breaks(4, &data), vec![3.0, 13.0, 30.0]);
+        assert_eq!(
+            try_get_maximum_breaks_with_min_class_size(4, 2, &data),
+            Ok(vec![0.5, 3.0, 13.0])
+        );
+        assert_eq!(
+            try_get_maximum_breaks_with_min_class_size(4, 3, &data),
+            Ok(vec![0.5, 13.0])
+        );
+        assert_eq!(get_maximum_breaks(20, &data).len(), 5);
+    }
+
+    #[test]
+    fn test_geometric_interval_skewed_and_crossing_zero() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut data: Vec<f64> = vec![];
+        for _ in 0..500 {
+            data.push(rng.gen_range(0.0..8.0_f64).exp() - 50.0);
+        }
+        let score = |breaks: &[f64]| -> u64 {
+            let class = breaks_to_classification(breaks, &data);
+            class.iter().map(|bin| bin.count * bin.count).sum()
+        };
+
+        let breaks = get_geometric_interval_breaks(5, &data);
+        let widths: Vec<f64> = breaks.windows(2).map(|pair| pair[1] - pair[0]).collect();
+
+        assert_eq!(breaks.len(), 4);
+        assert!(breaks[0] < 0.0 && breaks[3] > 0.0);
+        assert!(widths.windows(2).all(|pair| pair[1] > pair[0]));
+        assert!((widths[1] / widths[0] - widths[2] / widths[1]).abs() < 1e-9);
+        assert!(score(&breaks) < score(&get_equal_interval_breaks(5, &data)));
+        assert_eq!(
+            get_geometric_interval_breaks(5, &[2.0, 2.0]),
+            Vec::<f64>::new()
+        );
+    }
+
+    #[test]
+    fn test_pretty_breaks_are_nice() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let scale = 10_f64.powi(rng.gen_range(-3..5));
+            let data: Vec<f64> = (0..20).map(|_| rng.gen_range(-1.0..1.0) * scale).collect();
+            let num_bins = rng.gen_range(2..10);
+
+            let breaks = get_pretty_breaks(num_bins, &data);
+            let steps: Vec<f64> = breaks.windows(2).map(|pair| pair[1] - pair[0]).collect();
+            for step in &steps {
+                let mantissa = step / 10_f64.powf(step.log10().floor());
+                assert!([1.0, 2.0, 2.5, 5.0]
+                    .iter()
+                    .any(|m| (mantissa - m).abs() < 1e-6));
+                assert!((step - steps[0]).abs() < steps[0] * 1e-9);
+            }
+
+            let equal_breaks = get_equal_interval_breaks(num_bins, &data);
+            let tolerance = scale * 0.05;
+            let snapped = snap_breaks_to_nice(&equal_breaks, tolerance, &data);
+            for item in &snapped.breaks {
+                assert!(equal_breaks.iter().any(|b| (b - item).abs() <= tolerance));
+            }
+            assert_eq!(snapped.gvf_change, snapped.gvf_after - snapped.gvf_before);
+        }
+    }
+
+    #[test]
+    fn test_user_defined_validation() {
+        let data: Vec<f64> = vec![2.0, 7.0, 12.0, 30.0];
+
+        let method: UserDefined = " < -5 ,10,  0, 40 ".parse().unwrap();
+        assert_eq!(method.edges, vec![-5.0, 0.0, 10.0, 40.0]);
+        assert!(method.open_lower && !method.open_upper);
+        assert_eq!(method.breaks(&data), Ok(vec![10.0]));
+
+        assert!("".parse::<UserDefined>().is_err());
+        assert!("0, >10, 20".parse::<UserDefined>().is_err());
+        assert!("0, NaN".parse::<UserDefined>().is_err());
+        assert!(">10".parse::<UserDefined>().is_ok());
+
+        let unnormalized = UserDefined {
+            edges: vec![40.0, 0.0, 10.0, 10.0],
+            open_lower: false,
+            open_upper: false,
+        };
+        assert_eq!(unnormalized.breaks(&data), Ok(vec![10.0]));
+
+        let method: ClassificationMethod = method.into();
+        let json = serde_json::to_string(&method).unwrap();
+        let restored: ClassificationMethod = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.classify(&data).unwrap().method, Some(method));
+    }
+
+    #[test]
+    fn test_defined_interval_anchor_and_cap() {
+        let data: Vec<f64> = vec![-7.5, 3.0, 10.0, 14.0, 29.5];
+
+        let anchored = |width: f64, anchor: f64| {
+            try_get_defined_interval_breaks_with_options(width, Some(anchor), None, &data).unwrap()
+        };
+        assert_eq!(anchored(10.0, 0.0), vec![0.0, 10.0, 20.0]);
+        assert_eq!(anchored(10.0, 1000.0), vec![0.0, 10.0, 20.0]);
+        assert_eq!(anchored(10.0, -7.5), vec![2.5, 12.5, 22.5]);
+        assert_eq!(anchored(100.0, 0.0), vec![0.0]);
+        assert_eq!(anchored(100.0, 50.0), Vec::<f64>::new());
+        assert_eq!(
+            get_defined_interval_breaks(10.0, &data),
+            anchored(10.0, -7.5)
+        );
+
+        let classification = DefinedInterval {
+            width: 10.0,
+            anchor: Some(0.0),
+            max_classes: Some(4),
+        }
+        .classify(&data)
+        .unwrap();
+        assert_eq!(
+            classification
+                .iter()
+                .map(|bin| bin.count)
+                .collect::<Vec<u64>>(),
+            vec![1, 1, 2, 1]
+        );
+
+        assert!(
+            try_get_defined_interval_breaks_with_options(10.0, Some(0.0), Some(3), &data).is_err()
+        );
+        assert_eq!(
+            try_get_defined_interval_breaks_with_options(10.0, None, Some(0), &data),
+            Err(ClassifyError::InvalidBinCount(0))
+        );
+        assert!(try_get_defined_interval_breaks(-1.0, &data).is_err());
+        assert!(
+            try_get_defined_interval_breaks_with_options(1.0, Some(f64::NAN), None, &data).is_err()
+        );
+        assert_eq!(
+            get_defined_interval_breaks(1.0, &[4.0, 4.0]),
+            Vec::<f64>::new()
+        );
+
+        // Widths that can't be resolved at the magnitude of the anchor, or that would produce an unbounded number of classes, are rejected
+        let small: Vec<f64> = vec![0.0, 5.0, 10.0];
+        for anchor in [1e20, -1e20] {
+            assert!(matches!(
+                try_get_defined_interval_breaks_with_options(1.0, Some(anchor), Some(50), &small),
+                Err(ClassifyError::InvalidParameter(_))
+            ));
+        }
+        assert!(matches!(
+            try_get_defined_interval_breaks(1e-9, &[0.0, 1e6]),
+            Err(ClassifyError::InvalidParameter(_))
+        ));
+        assert_eq!(
+            try_get_defined_interval_breaks_with_options(1e6, Some(1e15), None, &small),
+            Ok(vec![])
+        );
+    }
+
     #[test]
     fn test_calc_gvf() {
         let mut unique_val_map: Vec<UniqueVal> = vec![];
@@ -144,6 +607,907 @@ mod tests {
         assert_eq!(jenks::calc_gssd(&data), 37.5);
     }
 
+    #[test]
+    fn test_classification_method_dispatch() {
+        let data: Vec<f64> = vec![
+            0.0, 1.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0, 20.0, 25.0,
+        ];
+        let methods: Vec<(ClassificationMethod, Vec<f64>)> = vec![
+            (Jenks { num_bins: 3 }.into(), get_jenks_breaks(3, &data)),
+            (
+                Quantile {
+                    num_bins: 4,
+                    definition: QuantileDefinition::Midpoint,
+                }
+                .into(),
+                get_quantile_breaks(4, &data),
+            ),
+            (
+                Percentiles {
+                    percentiles: vec![5.0, 50.0, 95.0],
+                    definition: QuantileDefinition::Type7,
+                }
+                .into(),
+                get_percentile_breaks(&[5.0, 50.0, 95.0], &data),
+            ),
+            (
+                JenksCaspall {
+                    num_bins: 3,
+                    start: JenksCaspallStart::EqualInterval,
+                }
+                .into(),
+                get_jenks_caspall_breaks(3, JenksCaspallStart::EqualInterval, &data),
+            ),
+            (Ckmeans { num_bins: 4 }.into(), get_ckmeans_breaks(4, &data)),
+            (
+                MaximumBreaks {
+                    num_bins: 4,
+                    min_class_size: 1,
+                }
+                .into(),
+                get_maximum_breaks(4, &data),
+            ),
+            (
+                GeometricInterval { num_bins: 3 }.into(),
+                get_geometric_interval_breaks(3, &data),
+            ),
+            (Pretty { num_bins: 5 }.into(), get_pretty_breaks(5, &data)),
+            (HeadTail.into(), get_head_tail_breaks(&data)),
+            (
+                EqualInterval { num_bins: 5 }.into(),
+                get_equal_interval_breaks(5, &data),
+            ),
+            (
+                DefinedInterval {
+                    width: 4.0,
+                    anchor: None,
+                    max_classes: None,
+                }
+                .into(),
+                get_defined_interval_breaks(4.0, &data),
+            ),
+            (
+                StdDev { bin_size: 0.5 }.into(),
+                get_st_dev_breaks(0.5, &data),
+            ),
+            (
+                Hinge {
+                    hinge_coefficient: 1.5,
+                }
+                .into(),
+                get_hinge_breaks(1.5, &data),
+            ),
+        ];
+
+        for (method, expected) in methods {
+            assert_eq!(
+                method.breaks(&data),
+                Ok(expected),
+                "{:?} not dispatched correctly!",
+                method
+            );
+        }
+    }
+
+    #[test]
+    fn test_breaks_to_classification_repeated_max() {
+        let data: Vec<f64> = vec![1.0, 2.0, 3.0, 3.0, 3.0];
+        let result = breaks_to_classification(&[2.0], &data);
+
+        assert_eq!(result[0].count, 1);
+        assert_eq!(result[1].count, 4, "repeated maximum not counted!");
+        assert_eq!(result.num_vals, 5);
+    }
+
+    #[test]
+    fn test_select_num_bins_rules() {
+        let data: Vec<f64> = (0..100).map(|i| (i * i) as f64).collect();
+        let method: ClassificationMethod = EqualInterval { num_bins: 1 }.into();
+        let expected = vec![
+            (AutoStrategy::Sturges, 8),
+            (AutoStrategy::Scott, 5),
+            (AutoStrategy::FreedmanDiaconis, 5),
+            (AutoStrategy::Doane, 10),
+            (AutoStrategy::JenksCaspall, 10),
+        ];
+
+        for (strategy, num_bins) in expected {
+            let result = select_num_bins(method.clone(), strategy, 20, &data).unwrap();
+            assert_eq!(result.num_bins, num_bins, "{:?} not working!", strategy);
+            assert_eq!(result.scores.len(), 20);
+        }
+    }
+
+    #[test]
+    fn test_select_num_bins_elbow() {
+        let data: Vec<f64> = vec![1.0, 1.1, 1.2, 5.0, 5.1, 5.2, 9.0, 9.1, 9.2, 9.3];
+        let result = select_num_bins(
+            Jenks { num_bins: 1 }.into(),
+            AutoStrategy::GvfElbow,
+            8,
+            &data,
+        );
+
+        assert_eq!(result.unwrap().num_bins, 3);
+    }
+
+    #[test]
+    fn test_classify_values_matches_classify_val() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let data: Vec<f64> = (0..1000).map(|_| rng.gen_range(-50.0..50.0)).collect();
+        let class = get_quantile_classification(7, &data);
+        let mut vals: Vec<f64> = (0..200).map(|_| rng.gen_range(-60.0..60.0)).collect();
+        vals.push(f64::NAN);
+
+        let expected: Vec<Option<usize>> =
+            vals.iter().map(|val| classify_val(*val, &class)).collect();
+        let result = classify_values(&vals, &class);
+
+        assert_eq!(result, expected);
+        assert_eq!(
+            class.iter().map(|bin| bin.count).sum::<u64>(),
+            1000,
+            "breaks_to_classification not counting every value!"
+        );
+    }
+
+    #[test]
+    fn test_missing_policy() {
+        let data: Vec<f64> = vec![1.0, f64::INFINITY, 2.0, -9999.0, 7.0, 8.0, f64::NAN];
+        let method: ClassificationMethod = Jenks { num_bins: 2 }.into();
+
+        let skipped = method.classify_with_policy(&data, &MissingPolicy::Skip);
+        assert_eq!(skipped.unwrap().num_skipped, 2);
+
+        let no_data = method
+            .classify_with_policy(&data, &MissingPolicy::NoDataBin(vec![-9999.0]))
+            .unwrap();
+        assert_eq!(no_data.breaks(), vec![7.0]);
+        assert_eq!(no_data.num_vals, 4);
+        assert_eq!(no_data.no_data_count, Some(3));
+
+        // Missing values are classified into the no-data bin, a real bin following the regular bins
+        assert_eq!(no_data.no_data_index(), Some(2));
+        assert_eq!((no_data.len(), no_data.value_bins().len()), (3, 2));
+        assert_eq!(no_data.labels().len(), no_data.len());
+        assert_eq!(no_data.labels().last().unwrap(), "No data");
+        assert_eq!(no_data[2].count, 3);
+        assert_eq!(no_data.clone(), no_data);
+        assert_eq!(no_data[classify_val(-9999.0, &no_data).unwrap()].count, 3);
+        assert_eq!(classify_val(-9999.0, &no_data), Some(2));
+        assert_eq!(classify_val(f64::NAN, &no_data), Some(2));
+        assert_eq!(
+            classify_values(&data, &no_data),
+            vec![
+                Some(0),
+                Some(2),
+                Some(0),
+                Some(2),
+                Some(1),
+                Some(1),
+                Some(2)
+            ]
+        );
+        let skipped = method
+            .classify_with_policy(&data, &MissingPolicy::Sentinels(vec![1.0]))
+            .unwrap();
+        assert_eq!(skipped.no_data_index(), None);
+        assert_eq!(try_classify_val(f64::NAN, &skipped), Ok(None));
+        assert_eq!(classify_val(1.0, &skipped), None);
+
+        // Weighted data drops the weights of skipped values, and SortedData can skip them up front
+        let weights: Vec<f64> = vec![1.0, 5.0, 1.0, 5.0, 1.0, 1.0, 5.0];
+        let weighted = method
+            .classify_weighted_with_policy(
+                &data,
+                &weights,
+                &MissingPolicy::NoDataBin(vec![-9999.0]),
+            )
+            .unwrap();
+        assert_eq!(weighted.breaks(), no_data.breaks());
+        assert_eq!(weighted.no_data_count, Some(3));
+        assert_eq!(weighted[2].weight_sum, 15.0);
+        assert_eq!(
+            method.breaks_weighted_with_policy(&data, &weights, &MissingPolicy::Error),
+            Err(ClassifyError::NonFiniteValue(1))
+        );
+        let (sorted, num_skipped) =
+            SortedData::try_new_with_policy(&data, &MissingPolicy::Sentinels(vec![-9999.0]))
+                .unwrap();
+        assert_eq!(num_skipped, 3);
+        assert_eq!(method.breaks_sorted(&sorted).unwrap(), no_data.breaks());
+
+        let all_missing: Vec<f64> = vec![f64::NAN, -9999.0];
+        assert_eq!(
+            method.breaks_with_policy(&all_missing, &MissingPolicy::Sentinels(vec![-9999.0])),
+            Err(ClassifyError::EmptyInput)
+        );
+    }
+
+    #[test]
+    fn test_closure_consistency() {
+        let data: Vec<f64> = vec![1.0, 2.0, 2.0, 4.0, 5.0, 5.0, 7.0, 8.0];
+        let breaks: Vec<f64> = vec![2.0, 5.0];
+
+        for closure in [Closure::LeftClosed, Closure::RightClosed] {
+            let class = try_breaks_to_classification_with_closure(&breaks, &data, closure).unwrap();
+            let indices = classify_values(&data, &class);
+
+            let mut counts: Vec<u64> = vec![0; class.len()];
+            for (val, idx) in data.iter().zip(indices.iter()) {
+                assert_eq!(classify_val(*val, &class), *idx);
+                counts[idx.unwrap()] += 1;
+            }
+            assert_eq!(
+                class.iter().map(|bin| bin.count).collect::<Vec<u64>>(),
+                counts
+            );
+            assert!(class.iter().all(|bin| bin.closure == closure));
+            assert_eq!(Classification::from(class.bins.clone()).closure, closure);
+        }
+
+        let right = try_breaks_to_classification_with_closure(&breaks, &data, Closure::RightClosed)
+            .unwrap();
+        assert_eq!(
+            right.iter().map(|bin| bin.count).collect::<Vec<u64>>(),
+            vec![3, 3, 2]
+        );
+        assert_eq!(right.labels(), vec!["[1, 2]", "(2, 5]", "(5, 8]"]);
+        assert_eq!(
+            metrics::evaluate_classification(&right, &data)
+                .unwrap()
+                .class_sdam[0],
+            2.0 / 3.0
+        );
+    }
+
+    #[test]
+    fn test_weighted_matches_repeated_data() {
+        let data: Vec<f64> = vec![3.0, 1.0, 7.0, 4.0, 12.0, 9.0, 2.0, 15.0, 6.0, 30.0];
+        let weights: Vec<u32> = vec![2, 1, 3, 1, 4, 2, 1, 1, 5, 1];
+        let mut repeated: Vec<f64> = vec![];
+        for (val, weight) in data.iter().zip(weights.iter()) {
+            repeated.extend(std::iter::repeat(*val).take(*weight as usize));
+        }
+
+        let methods: Vec<ClassificationMethod> = vec![
+            Jenks { num_bins: 3 }.into(),
+            JenksCaspall {
+                num_bins: 3,
+                start: JenksCaspallStart::Quantile,
+            }
+            .into(),
+            Ckmeans { num_bins: 4 }.into(),
+            GeometricInterval { num_bins: 3 }.into(),
+            Quantile {
+                num_bins: 4,
+                definition: QuantileDefinition::Type7,
+            }
+            .into(),
+            Percentiles {
+                percentiles: vec![10.0, 50.0, 90.0],
+                definition: QuantileDefinition::Type6,
+            }
+            .into(),
+            HeadTail.into(),
+            Hinge {
+                hinge_coefficient: 1.5,
+            }
+            .into(),
+            EqualInterval { num_bins: 5 }.into(),
+        ];
+        for method in &methods {
+            let weighted = method.classify_weighted(&data, &weights).unwrap();
+            let expected = method.classify(&repeated).unwrap();
+            assert_eq!(weighted.breaks(), expected.breaks(), "{:?}", method);
+            assert_eq!(
+                weighted
+                    .iter()
+                    .map(|bin| bin.weight_sum)
+                    .collect::<Vec<f64>>(),
+                expected
+                    .iter()
+                    .map(|bin| bin.count as f64)
+                    .collect::<Vec<f64>>()
+            );
+        }
+
+        // The weighted standard deviation treats weights as reliability weights, so it only depends on their relative sizes
+        let method = StdDev { bin_size: 1.0 };
+        let st_dev_data: Vec<f64> = vec![0.0, 1.0, 2.0, 3.0, 100.0];
+        let expected = method.breaks(&st_dev_data).unwrap();
+        for weight in [0.001, 0.1, 0.25, 0.5, 1.0, 3.0] {
+            let result = method
+                .breaks_weighted(&st_dev_data, &vec![weight; st_dev_data.len()])
+                .unwrap();
+            assert_eq!(result.len(), expected.len());
+            for (brk, expected_brk) in result.iter().zip(expected.iter()) {
+                assert!((brk - expected_brk).abs() < 1e-9);
+            }
+        }
+        let scaled: Vec<f64> = weights.iter().map(|weight| *weight as f64 * 0.01).collect();
+        let scaled_result = method.breaks_weighted(&data, &scaled).unwrap();
+        let weighted_result = method.breaks_weighted(&data, &weights).unwrap();
+        assert_eq!(scaled_result.len(), weighted_result.len());
+        for (brk, expected_brk) in scaled_result.iter().zip(weighted_result.iter()) {
+            assert!((brk - expected_brk).abs() < 1e-9);
+        }
+
+        // Every method leaves out points whose weight is zero, including those that otherwise ignore the weights
+        let zero_weighted: Vec<f64> = vec![1.0, 1.0, 1.0, 1.0, 0.0];
+        let range_methods: Vec<ClassificationMethod> = vec![
+            EqualInterval { num_bins: 3 }.into(),
+            Pretty { num_bins: 3 }.into(),
+            DefinedInterval {
+                width: 1.0,
+                anchor: None,
+                max_classes: None,
+            }
+            .into(),
+            Jenks { num_bins: 3 }.into(),
+        ];
+        for method in &range_methods {
+            assert_eq!(
+                method.breaks_weighted(&st_dev_data, &zero_weighted),
+                method.breaks(&st_dev_data[..4]),
+                "{:?}",
+                method
+            );
+        }
+
+        assert!(Jenks { num_bins: 3 }
+            .breaks_weighted(&data, &weights[1..])
+            .is_err());
+        assert!(EqualInterval { num_bins: 3 }
+            .breaks_weighted(&data, &vec![-1.0; data.len()])
+            .is_err());
+        assert!(HeadTail
+            .breaks_weighted(&data, &vec![0; data.len()])
+            .is_err());
+    }
+
+    #[test]
+    fn test_histogram_matches_expanded_data() {
+        let histogram: Vec<(f64, u64)> = vec![
+            (4.0, 12),
+            (1.0, 3),
+            (9.0, 7),
+            (2.5, 20),
+            (4.0, 3),
+            (17.0, 2),
+            (6.0, 0),
+            (11.0, 9),
+            (40.0, 1),
+        ];
+        let mut expanded: Vec<f64> = vec![];
+        for (val, count) in &histogram {
+            expanded.extend(std::iter::repeat(*val).take(*count as usize));
+        }
+
+        let methods: Vec<ClassificationMethod> = vec![
+            Jenks { num_bins: 4 }.into(),
+            JenksCaspall {
+                num_bins: 3,
+                start: JenksCaspallStart::EqualInterval,
+            }
+            .into(),
+            Ckmeans { num_bins: 3 }.into(),
+            MaximumBreaks {
+                num_bins: 4,
+                min_class_size: 5,
+            }
+            .into(),
+            GeometricInterval { num_bins: 4 }.into(),
+            Pretty { num_bins: 4 }.into(),
+            Quantile {
+                num_bins: 5,
+                definition: Default::default(),
+            }
+            .into(),
+            Percentiles {
+                percentiles: vec![5.0, 25.0, 75.0, 95.0],
+                definition: QuantileDefinition::Type8,
+            }
+            .into(),
+            HeadTail.into(),
+            EqualInterval { num_bins: 3 }.into(),
+            DefinedInterval {
+                width: 10.0,
+                anchor: Some(0.0),
+                max_classes: None,
+            }
+            .into(),
+            StdDev { bin_size: 0.5 }.into(),
+            Hinge {
+                hinge_coefficient: 1.0,
+            }
+            .into(),
+            UserDefined::new(&[0.0, 5.0, 10.0, 50.0], false, false)
+                .unwrap()
+                .into(),
+        ];
+        for method in &methods {
+            let result = method.classify_from_histogram(&histogram).unwrap();
+            let expected = method.classify(&expanded).unwrap();
+            // Standard deviation breaks accumulate the mean in a different order, so breaks are compared up to rounding
+            assert_eq!(result.len(), expected.len(), "{:?}", method);
+            for (brk, expected_brk) in result.breaks().iter().zip(expected.breaks()) {
+                assert!((brk - expected_brk).abs() < 1e-9, "{:?}", method);
+            }
+            assert_eq!(
+                result.iter().map(|bin| bin.count).collect::<Vec<u64>>(),
+                expected.iter().map(|bin| bin.count).collect::<Vec<u64>>()
+            );
+            assert_eq!(result.num_vals, expanded.len());
+            assert_eq!((result.min, result.max), (1.0, 40.0));
+        }
+
+        assert_eq!(
+            Jenks { num_bins: 3 }.breaks_from_histogram(&[]),
+            Err(ClassifyError::EmptyInput)
+        );
+        assert_eq!(
+            Jenks { num_bins: 3 }.breaks_from_histogram(&[(1.0, 2), (f64::NAN, 1)]),
+            Err(ClassifyError::NonFiniteValue(1))
+        );
+        assert!(HeadTail.breaks_from_histogram(&[(1.0, 0)]).is_err());
+    }
+
+    #[test]
+    fn test_sorted_data_matches_unsorted() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let data: Vec<f64> = (0..500)
+            .map(|_| (rng.gen_range(0.0..1000.0_f64) / 4.0).round())
+            .collect();
+        let sorted = SortedData::new(&data);
+
+        assert_eq!(sorted.num_vals(), 500);
+        assert!(sorted.values().windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(sorted.prefix_sums().len(), 501);
+        assert_eq!(
+            sorted.prefix_sums()[500],
+            sorted.values().iter().sum::<f64>()
+        );
+        assert_eq!(
+            sorted.prefix_sums_squares()[3],
+            sorted.values()[..3]
+                .iter()
+                .map(|val| val * val)
+                .sum::<f64>()
+        );
+        assert_eq!(
+            sorted.unique_vals().last().unwrap().last,
+            sorted.num_vals() - 1
+        );
+        assert_eq!(
+            sorted.variance().sqrt(),
+            standard_deviation::calc_st_dev(&data)
+        );
+
+        let without_sums = SortedData::try_new_without_sums(&data).unwrap();
+        assert_eq!(without_sums.values(), sorted.values());
+        assert_eq!(without_sums.prefix_sums(), sorted.prefix_sums());
+        assert_eq!(
+            without_sums.prefix_sums_squares(),
+            sorted.prefix_sums_squares()
+        );
+        for (first, end) in [(0, 500), (10, 20), (499, 500), (7, 7)] {
+            assert!((without_sums.ssd(first, end) - sorted.ssd(first, end)).abs() < 1e-6);
+        }
+
+        let methods: Vec<ClassificationMethod> = vec![
+            Jenks { num_bins: 5 }.into(),
+            ApproxJenks {
+                num_bins: 5,
+                options: JenksOptions::default(),
+            }
+            .into(),
+            JenksCaspall {
+                num_bins: 5,
+                start: JenksCaspallStart::Quantile,
+            }
+            .into(),
+            Ckmeans { num_bins: 5 }.into(),
+            MaximumBreaks {
+                num_bins: 5,
+                min_class_size: 10,
+            }
+            .into(),
+            GeometricInterval { num_bins: 5 }.into(),
+            Pretty { num_bins: 5 }.into(),
+            Quantile {
+                num_bins: 5,
+                definition: Default::default(),
+            }
+            .into(),
+            Percentiles {
+                percentiles: vec![10.0, 90.0],
+                definition: QuantileDefinition::Type7,
+            }
+            .into(),
+            HeadTail.into(),
+            EqualInterval { num_bins: 5 }.into(),
+            DefinedInterval {
+                width: 100.0,
+                anchor: Some(0.0),
+                max_classes: None,
+            }
+            .into(),
+            StdDev { bin_size: 0.5 }.into(),
+            Hinge {
+                hinge_coefficient: 1.5,
+            }
+            .into(),
+            UserDefined::new(&[0.0, 100.0, 200.0, 250.0], false, false)
+                .unwrap()
+                .into(),
+        ];
+        for method in &methods {
+            assert_eq!(
+                method.breaks_sorted(&sorted),
+                method.breaks(&data),
+                "{:?}",
+                method
+            );
+            assert_eq!(
+                method.classify_sorted(&sorted).unwrap().bins,
+                method.classify(&data).unwrap().bins
+            );
+        }
+
+        assert!(UserDefined::new(&[0.0, 100.0], false, false)
+            .unwrap()
+            .breaks_sorted(&sorted)
+            .is_err());
+        assert_eq!(
+            SortedData::try_new(&Vec::<f64>::new()),
+            Err(ClassifyError::EmptyInput)
+        );
+    }
+
+    #[test]
+    fn test_prefix_sum_ssd() {
+        let mut rng = StdRng::seed_from_u64(99);
+        for offset in [0.0, 1e9] {
+            let data: Vec<f64> = (0..2000)
+                .map(|_| offset + rng.gen_range(0.0..10.0_f64))
+                .collect();
+            let sorted = SortedData::new(&data);
+
+            for _ in 0..100 {
+                let first = rng.gen_range(0..2000);
+                let end = rng.gen_range(first..=2000);
+                let range = &sorted.values()[first..end];
+                let expected = if range.is_empty() {
+                    0.0
+                } else {
+                    jenks::calc_gssd(range)
+                };
+                assert!((sorted.ssd(first, end) - expected).abs() <= 1e-9 * expected.max(1.0));
+            }
+
+            let breaks = vec![offset + 2.5, offset + 5.0, offset + 7.5];
+            let expected = metrics::evaluate(&breaks, &data).unwrap();
+            assert!((metrics::gvf_sorted(&breaks, &sorted).unwrap() - expected.gvf).abs() < 1e-12);
+            for (ssd, expected_ssd) in metrics::class_sdam(&breaks, &data)
+                .unwrap()
+                .iter()
+                .zip(expected.class_sdam)
+            {
+                assert!((ssd - expected_ssd).abs() <= 1e-9 * expected_ssd);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunked_classification_counts() {
+        // Large enough to span several counting chunks
+        let mut rng = StdRng::seed_from_u64(123456789);
+        let data: Vec<f64> = (0..200000).map(|_| rng.gen_range(0.0..100.0)).collect();
+        let weights: Vec<f64> = (0..data.len()).map(|_| rng.gen_range(0.5..2.0)).collect();
+        let breaks = vec![10.0, 35.5, 50.0, 90.0];
+
+        let result = try_breaks_to_classification_weighted(&breaks, &data, &weights).unwrap();
+        let indices = classify_values(&data, &result);
+
+        let mut counts = vec![0_u64; result.len()];
+        let mut weight_sums = vec![0.0; result.len()];
+        for (idx, weight) in indices.iter().zip(weights.iter()) {
+            counts[idx.unwrap()] += 1;
+            weight_sums[idx.unwrap()] += weight;
+        }
+        for i in 0..result.len() {
+            assert_eq!(result[i].count, counts[i]);
+            assert!((result[i].weight_sum - weight_sums[i]).abs() < 1e-6);
+        }
+        let fit = try_breaks_to_classification(&breaks, &data)
+            .unwrap()
+            .fit
+            .unwrap();
+        assert!((fit.gvf - metrics::gvf(&breaks, &data).unwrap()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_jenks_options() {
+        let mut rng = StdRng::seed_from_u64(123456789);
+        let data: Vec<f64> = (0..2000).map(|_| rng.gen_range(0.0..1000.0)).collect();
+
+        // The default options reproduce the original search, and the reported GVF matches the breaks
+        let default_search =
+            try_get_approx_jenks_search(5, &data, &JenksOptions::default()).unwrap();
+        assert_eq!(default_search.breaks, get_approx_jenks_breaks(5, &data));
+        assert_eq!(default_search.permutations, 10000);
+        let gvf = metrics::gvf(&default_search.breaks, &data).unwrap();
+        assert!((default_search.gvf - gvf).abs() < 1e-9);
+
+        // Passing an RNG seeded like the defaults is equivalent to using the seed
+        let mut seeded_rng = StdRng::seed_from_u64(123456789);
+        let rng_search = try_get_approx_jenks_search_with_rng(
+            5,
+            &data,
+            &JenksOptions::default(),
+            &mut seeded_rng,
+        )
+        .unwrap();
+        assert_eq!(rng_search, default_search);
+
+        // The first restart reproduces a single search, while later restarts draw their own random sequences instead of continuing it
+        let permutation_gvfs = |options: JenksOptions| {
+            let mut gvfs: Vec<f64> = vec![];
+            let breaks = ApproxJenks {
+                num_bins: 5,
+                options,
+            }
+            .breaks_observed(&data, &mut |event: &Event| {
+                if let Event::Permutation { gvf, .. } = event {
+                    gvfs.push(*gvf);
+                }
+            })
+            .unwrap();
+            (breaks, gvfs)
+        };
+        let options = JenksOptions {
+            budget: JenksBudget::Permutations(500),
+            restarts: 3,
+            ..Default::default()
+        };
+        let search = try_get_approx_jenks_search(5, &data, &options).unwrap();
+        assert_eq!(search.permutations, 1500);
+        let (breaks, restarts_gvfs) = permutation_gvfs(options);
+        assert_eq!(breaks, search.breaks);
+        let (_, single_gvfs) = permutation_gvfs(JenksOptions {
+            restarts: 1,
+            ..options
+        });
+        let (_, longer_gvfs) = permutation_gvfs(JenksOptions {
+            budget: JenksBudget::Permutations(1500),
+            restarts: 1,
+            ..options
+        });
+        assert_eq!(restarts_gvfs[..500], single_gvfs[..]);
+        assert_eq!(restarts_gvfs[..500], longer_gvfs[..500]);
+        assert_ne!(restarts_gvfs[500..], longer_gvfs[500..]);
+        assert_ne!(restarts_gvfs[500..1000], restarts_gvfs[1000..]);
+        let best_gvf = restarts_gvfs.iter().cloned().fold(0.0, f64::max);
+        assert_eq!(search.gvf, best_gvf);
+        assert!(search.gvf >= single_gvfs.iter().cloned().fold(0.0, f64::max));
+
+        // Early stopping ends every restart after `patience` permutations without improvement
+        let options = JenksOptions {
+            tolerance: 0.01,
+            patience: Some(50),
+            ..Default::default()
+        };
+        let search = try_get_approx_jenks_search(5, &data, &options).unwrap();
+        assert!(search.permutations < 10000);
+        assert_eq!(
+            ApproxJenks {
+                num_bins: 5,
+                options
+            }
+            .breaks(&data)
+            .unwrap(),
+            search.breaks
+        );
+
+        // A time budget always tries at least one batch of permutations per restart
+        let options = JenksOptions {
+            budget: JenksBudget::Time(std::time::Duration::from_millis(0)),
+            restarts: 2,
+            ..Default::default()
+        };
+        let search = try_get_approx_jenks_search(5, &data, &options).unwrap();
+        assert!(search.permutations >= 2);
+        assert_eq!(search.breaks.len(), 4);
+
+        for options in [
+            JenksOptions {
+                restarts: 0,
+                ..Default::default()
+            },
+            JenksOptions {
+                budget: JenksBudget::Permutations(0),
+                ..Default::default()
+            },
+            JenksOptions {
+                tolerance: -1.0,
+                ..Default::default()
+            },
+            JenksOptions {
+                patience: Some(0),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                try_get_approx_jenks_search(5, &data, &options),
+                Err(ClassifyError::InvalidParameter(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_quantile_sketch() {
+        let mut rng = StdRng::seed_from_u64(123456789);
+        let data: Vec<f64> = (0..200000)
+            .map(|_| rng.gen_range(0.0_f64..10.0).exp())
+            .collect();
+
+        // Sketch four partitions chunk by chunk, then merge them
+        let mut sketch = QuantileSketch::default();
+        for partition in data.chunks(50000) {
+            let mut partial = QuantileSketch::default();
+            for chunk in partition.chunks(4096) {
+                partial.try_extend(chunk).unwrap();
+            }
+            sketch.merge(&partial);
+        }
+        assert_eq!(sketch.num_vals(), 200000);
+        assert!(sketch.num_retained() < 2000);
+
+        let sorted = SortedData::new(&data);
+        assert_eq!(sketch.min(), Some(sorted.min()));
+        assert_eq!(sketch.max(), Some(sorted.max()));
+        let true_rank = |val: f64| -> f64 {
+            sorted.values().partition_point(|item| *item <= val) as f64 / data.len() as f64
+        };
+        let error = sketch.rank_error();
+
+        let quantile = Quantile {
+            num_bins: 5,
+            definition: Default::default(),
+        };
+        let breaks = quantile.breaks_from_sketch(&sketch).unwrap();
+        assert_eq!(breaks.len(), 4);
+        for (i, item) in breaks.iter().enumerate() {
+            assert!((true_rank(*item) - (i + 1) as f64 / 5.0).abs() <= error);
+            assert!((sketch.rank(*item) - true_rank(*item)).abs() <= error);
+        }
+
+        let hinge = Hinge {
+            hinge_coefficient: 1.5,
+        };
+        let exact = hinge.breaks(&data).unwrap();
+        let approx = hinge.breaks_from_sketch(&sketch).unwrap();
+        assert_eq!(approx.len(), exact.len());
+        for (a, b) in approx.iter().zip(exact.iter()) {
+            assert!((true_rank(*a) - true_rank(*b)).abs() <= 2.0 * error);
+        }
+
+        // The first head-tail break is the mean, which the weighted samples estimate closely
+        let exact = HeadTail.breaks(&data).unwrap();
+        let approx = HeadTail.breaks_from_sketch(&sketch).unwrap();
+        assert!((true_rank(approx[0]) - true_rank(exact[0])).abs() <= 2.0 * error);
+
+        // Sketches are reproducible and merging is insensitive to how the data was split
+        let mut whole = QuantileSketch::default();
+        whole.try_extend(&data).unwrap();
+        let mut again = QuantileSketch::default();
+        again.try_extend(&data).unwrap();
+        assert_eq!(whole, again);
+        let median = whole.try_quantile(0.5).unwrap();
+        assert!((true_rank(median) - 0.5).abs() <= error);
+        assert!((true_rank(sketch.try_quantile(0.5).unwrap()) - 0.5).abs() <= error);
+
+        assert_eq!(
+            sketch.try_extend(&[1.0, f64::NAN]),
+            Err(ClassifyError::NonFiniteValue(1))
+        );
+        assert_eq!(sketch.num_vals(), 200000);
+        assert_eq!(
+            QuantileSketch::default().try_quantile(0.5),
+            Err(ClassifyError::EmptyInput)
+        );
+
+        // Empty and filled sketches survive a JSON round trip, while inconsistent ones are rejected
+        // (integer data, since serde_json doesn't round-trip every float exactly by default)
+        let mut integers = QuantileSketch::default();
+        integers
+            .try_extend(&(0..100000).collect::<Vec<u32>>())
+            .unwrap();
+        for original in [QuantileSketch::default(), integers] {
+            let json = serde_json::to_string(&original).unwrap();
+            let restored: QuantileSketch = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored, original);
+        }
+        let mut merged = QuantileSketch::default();
+        merged.merge(
+            &serde_json::from_str(&serde_json::to_string(&QuantileSketch::default()).unwrap())
+                .unwrap(),
+        );
+        assert!(merged.is_empty() && merged.min().is_none());
+        for json in [
+            r#"{"k":4,"levels":[[]],"num_vals":0,"min":null,"max":null,"coin":1}"#,
+            r#"{"k":200,"levels":[],"num_vals":0,"min":null,"max":null,"coin":1}"#,
+            r#"{"k":200,"levels":[[1.0]],"num_vals":2,"min":1.0,"max":1.0,"coin":1}"#,
+            r#"{"k":200,"levels":[[1.0]],"num_vals":1,"min":null,"max":null,"coin":1}"#,
+        ] {
+            assert!(serde_json::from_str::<QuantileSketch>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_zero_bins_wrappers() {
+        let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0];
+
+        assert_eq!(get_quantile_breaks(0, &data), Vec::<f64>::new());
+        assert_eq!(get_equal_interval_breaks(0, &data), Vec::<f64>::new());
+        assert_eq!(get_quantile_classification(0, &data).len(), 1);
+        assert_eq!(get_equal_interval_classification(0, &data).len(), 1);
+        assert_eq!(
+            try_get_quantile_breaks(0, &data),
+            Err(ClassifyError::InvalidBinCount(0))
+        );
+        assert_eq!(
+            try_get_equal_interval_breaks(0, &data),
+            Err(ClassifyError::InvalidBinCount(0))
+        );
+    }
+
+    #[test]
+    fn test_observed_events() {
+        let data: Vec<f64> = (1..=100).map(|i| 1.0 / i as f64).collect();
+        let mut levels: Vec<(usize, f64, usize)> = vec![];
+        let breaks = HeadTail
+            .breaks_observed(&data, &mut |event: &Event| {
+                if let Event::Level {
+                    index,
+                    mean,
+                    head_size,
+                } = event
+                {
+                    levels.push((*index, *mean, *head_size));
+                }
+            })
+            .unwrap();
+        assert_eq!(levels.len(), breaks.len());
+        for (i, (index, mean, head_size)) in levels.iter().enumerate() {
+            assert_eq!(*index, i);
+            assert_eq!(*mean, breaks[i]);
+            assert_eq!(*head_size, data.iter().filter(|val| **val >= *mean).count());
+        }
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let data: Vec<f64> = (0..200)
+            .map(|_| rng.gen_range(0.0..50.0_f64).powi(2))
+            .collect();
+        let mut events: Vec<Event> = vec![];
+        let breaks = JenksCaspall {
+            num_bins: 4,
+            start: JenksCaspallStart::EqualInterval,
+        }
+        .breaks_observed(&data, &mut |event: &Event| events.push(event.clone()))
+        .unwrap();
+        let refinement =
+            try_get_jenks_caspall_refinement(4, JenksCaspallStart::EqualInterval, &data).unwrap();
+        assert_eq!(breaks, refinement.breaks);
+        let mut expected = vec![Event::BestGvf(refinement.gvf_trajectory[0])];
+        for i in 0..refinement.iterations {
+            expected.push(Event::Iteration(i));
+            expected.push(Event::BestGvf(refinement.gvf_trajectory[i + 1]));
+        }
+        assert_eq!(events, expected);
+    }
+
     #[test]
     fn test_calc_st_dev() {
         let data: Vec<f64> = vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0];
@@ -0,0 +1,301 @@
+use num_traits::ToPrimitive;
+
+use crate::classifier::{Classifier, MaximumBreaks};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
+use crate::utilities::{try_to_weighted_vals, unique_to_weighted_vals};
+use crate::utilities::{Classification, WeightedVal};
+
+/// Returns a Classification object following the Maximum Breaks algorithm given the desired number of bins and one-dimensional data
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * Panics on empty data, non-finite data or zero bins; use try_get_maximum_breaks_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_maximum_breaks_classification;
+/// use classify::{Classification, Bin};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 10.0, 11.0, 30.0];
+///
+/// let result: Classification = get_maximum_breaks_classification(3, &data);
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(1.0, 6.5, 3),
+///     Bin::new(6.5, 20.5, 2),
+///     Bin::new(20.5, 30.0, 1)
+/// ];
+///
+/// assert!(result == expected);
+/// ```
+pub fn get_maximum_breaks_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Classification {
+    try_get_maximum_breaks_classification(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Maximum Breaks algorithm given the desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_maximum_breaks_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0];
+///
+/// assert_eq!(try_get_maximum_breaks_classification(0, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_maximum_breaks_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    MaximumBreaks {
+        num_bins,
+        min_class_size: 1,
+    }
+    .classify(data)
+}
+
+/// Returns a Classification object following the Maximum Breaks algorithm given the desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_maximum_breaks_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    MaximumBreaks {
+        num_bins,
+        min_class_size: 1,
+    }
+    .classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Maximum Breaks algorithm given the desired number of bins and a dataset
+///
+/// The breaks are placed at the midpoints of the largest gaps between consecutive unique values
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
+/// * Gaps of equal size are chosen from lowest to highest
+/// * Panics on empty data, non-finite data or zero bins; use try_get_maximum_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_maximum_breaks;
+///
+/// let data: Vec<i32> = vec![1, 2, 3, 10, 11, 30];
+///
+/// let result: Vec<f64> = get_maximum_breaks(3, &data);
+///
+/// assert_eq!(result, vec![6.5, 20.5]);
+/// ```
+pub fn get_maximum_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64> {
+    try_get_maximum_breaks(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Maximum Breaks algorithm given the desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_maximum_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 10.0, 11.0, 30.0];
+///
+/// assert_eq!(try_get_maximum_breaks(2, &data), Ok(vec![20.5]));
+/// assert_eq!(try_get_maximum_breaks(2, &Vec::<f64>::new()), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_maximum_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    try_get_maximum_breaks_with_min_class_size(num_bins, 1, data)
+}
+
+/// Returns a vector of breaks generated through the Maximum Breaks algorithm given the desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_maximum_breaks, try_get_maximum_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_maximum_breaks_with_policy(3, &data, &policy), try_get_maximum_breaks(3, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_maximum_breaks_with_policy(3, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_maximum_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    MaximumBreaks {
+        num_bins,
+        min_class_size: 1,
+    }
+    .breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Maximum Breaks algorithm given the desired number of bins, the minimum number of data points per bin and a dataset, or a ClassifyError if the input is invalid
+///
+/// Gaps are considered from largest to smallest, and a gap is skipped if breaking at it would leave a bin with fewer than min_class_size data points
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `min_class_size` - The minimum number of data points in every bin (0 and 1 both mean no constraint)
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Fewer than num_bins bins are produced if there aren't enough gaps satisfying the constraint
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_maximum_breaks_with_min_class_size;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 10.0, 11.0, 30.0];
+///
+/// assert_eq!(try_get_maximum_breaks_with_min_class_size(3, 2, &data), Ok(vec![6.5]));
+/// assert_eq!(try_get_maximum_breaks_with_min_class_size(3, 1, &data), Ok(vec![6.5, 20.5]));
+/// ```
+pub fn try_get_maximum_breaks_with_min_class_size<T: ToPrimitive>(
+    num_bins: usize,
+    min_class_size: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    try_get_maximum_breaks_sorted(
+        num_bins,
+        min_class_size,
+        &SortedData::try_new_without_sums(data)?,
+    )
+}
+
+/// Returns a vector of breaks generated through the Maximum Breaks algorithm given the desired number of bins, the minimum number of data points per bin and a SortedData object, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_maximum_breaks_sorted(
+    num_bins: usize,
+    min_class_size: usize,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    Ok(calc_maximum_breaks(
+        &unique_to_weighted_vals(sorted.unique_vals()),
+        num_bins,
+        min_class_size,
+    ))
+}
+
+/// Returns a vector of breaks generated through the Maximum Breaks algorithm given the desired number of bins, the minimum total weight per bin and a weighted dataset, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_maximum_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    num_bins: usize,
+    min_class_size: usize,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+
+    Ok(calc_maximum_breaks(
+        &weighted_vals,
+        num_bins,
+        min_class_size,
+    ))
+}
+
+/// Returns the breaks at the largest gaps between sorted weighted unique values given the desired number of bins (at least one) and the minimum total weight per bin (0 and 1 both mean no constraint)
+fn calc_maximum_breaks(
+    unique_val_map: &[WeightedVal],
+    num_bins: usize,
+    min_class_size: usize,
+) -> Vec<f64> {
+    // The total weight below every unique value locates it within the data
+    let mut positions: Vec<f64> = vec![0.0];
+    for item in unique_val_map {
+        positions.push(positions[positions.len() - 1] + item.weight);
+    }
+    let total_weight = positions[unique_val_map.len()];
+    let min_weight = min_class_size as f64;
+
+    // Gaps are identified by the index of the unique value just above them and sorted (stably) from largest to smallest
+    let mut gaps: Vec<usize> = (1..unique_val_map.len()).collect();
+    gaps.sort_by(|a, b| {
+        let gap_a = unique_val_map[*a].val - unique_val_map[*a - 1].val;
+        let gap_b = unique_val_map[*b].val - unique_val_map[*b - 1].val;
+        gap_b.partial_cmp(&gap_a).unwrap()
+    });
+
+    let mut chosen: Vec<usize> = vec![];
+    for gap in gaps {
+        if chosen.len() + 1 >= num_bins {
+            break;
+        }
+        let pos = chosen.partition_point(|item| *item < gap);
+        let class_start = pos.checked_sub(1).map_or(0.0, |i| positions[chosen[i]]);
+        let class_end = chosen.get(pos).map_or(total_weight, |i| positions[*i]);
+        let split = positions[gap];
+        if min_class_size <= 1
+            || (split - class_start >= min_weight && class_end - split >= min_weight)
+        {
+            chosen.insert(pos, gap);
+        }
+    }
+
+    let mut breaks: Vec<f64> = vec![];
+    for item in chosen {
+        breaks.push((unique_val_map[item - 1].val + unique_val_map[item].val) / 2.0);
+    }
+
+    breaks
+}
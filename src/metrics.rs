@@ -0,0 +1,361 @@
+//! Goodness-of-fit measures that can be computed for any set of breaks (or Classification) on a dataset, regardless of the algorithm that produced them
+//!
+//! Data points are assigned to classes the same way as in breaks_to_classification: each class includes its lowest value and excludes its highest value, except the last class, which also includes the maximum of the dataset
+//!
+//! When evaluating a Classification, its closure is honored instead, so right-closed classes include their highest value and exclude their lowest value (except the first class)
+
+use num_traits::ToPrimitive;
+
+use crate::error::ClassifyError;
+use crate::sorted_data::SortedData;
+use crate::utilities::try_to_vec_f64;
+use crate::utilities::{Classification, Closure};
+
+/// Represents every goodness-of-fit measure for a set of breaks on a dataset
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    /// Goodness of variance fit (GVF), between 0 and 1 (higher is better)
+    pub gvf: f64,
+    /// Tabular accuracy index (TAI), between 0 and 1 (higher is better)
+    pub tai: f64,
+    /// Sum of absolute deviations around class medians (ADCM)
+    pub adcm: f64,
+    /// Goodness of absolute deviation fit (GADF), between 0 and 1 (higher is better)
+    pub gadf: f64,
+    /// Sum of squared deviations of each class around its own mean (the SDAM of each class treated as its own array)
+    pub class_sdam: Vec<f64>,
+    /// Jenks-Caspall (tabular) error: the sum of absolute deviations around class means
+    pub jenks_caspall_error: f64,
+}
+
+/// Returns every goodness-of-fit measure for a set of breaks on a dataset
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::metrics;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+/// let breaks: Vec<f64> = vec![4.0, 7.0];
+///
+/// let result = metrics::evaluate(&breaks, &data).unwrap();
+///
+/// assert_eq!(result.gvf, 0.96);
+/// assert_eq!(result.adcm, 3.0);
+/// assert_eq!(result.class_sdam, vec![0.5, 0.5, 0.5]);
+/// assert_eq!(result.jenks_caspall_error, 3.0);
+/// ```
+pub fn evaluate<T: ToPrimitive>(breaks: &[f64], data: &[T]) -> Result<Metrics, ClassifyError> {
+    evaluate_with_closure(breaks, data, Closure::LeftClosed)
+}
+
+/// Returns every goodness-of-fit measure for a set of breaks on a dataset, assigning data points equal to a break according to a closure
+fn evaluate_with_closure<T: ToPrimitive>(
+    breaks: &[f64],
+    data: &[T],
+    closure: Closure,
+) -> Result<Metrics, ClassifyError> {
+    let sorted_data = sort_data(data)?;
+    let classes = class_ranges(breaks, &sorted_data, closure)?;
+
+    let num_vals = sorted_data.len();
+    let mean: f64 = sorted_data.iter().sum::<f64>() / num_vals as f64;
+    let median = calc_median(&sorted_data);
+
+    let mut sdam: f64 = 0.0;
+    let mut adam: f64 = 0.0;
+    let mut abs_dev_mean: f64 = 0.0;
+    for item in &sorted_data {
+        sdam += (item - mean) * (item - mean);
+        adam += (item - median).abs();
+        abs_dev_mean += (item - mean).abs();
+    }
+
+    let mut class_sdam: Vec<f64> = vec![];
+    let mut adcm: f64 = 0.0;
+    let mut jenks_caspall_error: f64 = 0.0;
+    for (lower, upper) in classes {
+        let class_data = &sorted_data[lower..upper];
+        if class_data.is_empty() {
+            class_sdam.push(0.0);
+            continue;
+        }
+        let class_mean: f64 = class_data.iter().sum::<f64>() / class_data.len() as f64;
+        let class_median = calc_median(class_data);
+        let mut ssd: f64 = 0.0;
+        for item in class_data {
+            ssd += (item - class_mean) * (item - class_mean);
+            adcm += (item - class_median).abs();
+            jenks_caspall_error += (item - class_mean).abs();
+        }
+        class_sdam.push(ssd);
+    }
+    let sdcm: f64 = class_sdam.iter().sum();
+
+    Ok(Metrics {
+        gvf: ratio_fit(sdcm, sdam),
+        tai: ratio_fit(jenks_caspall_error, abs_dev_mean),
+        adcm,
+        gadf: ratio_fit(adcm, adam),
+        class_sdam,
+        jenks_caspall_error,
+    })
+}
+
+/// Returns every goodness-of-fit measure for a Classification on a dataset
+///
+/// # Arguments
+///
+/// * `classification` - A reference to a Classification generated through any method
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::{get_quantile_classification, get_jenks_classification};
+/// use classify::metrics;
+///
+/// let data: Vec<f64> = vec![1.0, 1.5, 2.0, 8.0, 9.0, 9.5, 20.0];
+///
+/// let jenks = metrics::evaluate_classification(&get_jenks_classification(3, &data), &data).unwrap();
+/// let quantile = metrics::evaluate_classification(&get_quantile_classification(3, &data), &data).unwrap();
+///
+/// assert!(jenks.gvf >= quantile.gvf);
+/// ```
+pub fn evaluate_classification<T: ToPrimitive>(
+    classification: &Classification,
+    data: &[T],
+) -> Result<Metrics, ClassifyError> {
+    evaluate_with_closure(&classification.breaks(), data, classification.closure)
+}
+
+/// Returns the goodness of variance fit (GVF) for a set of breaks on a dataset, which is one minus the ratio of the sum of squared deviations around class means to the sum of squared deviations around the dataset mean
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::metrics;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(metrics::gvf(&[2.0, 5.0, 7.0], &data), Ok(0.9333333333333333));
+/// ```
+pub fn gvf<T: ToPrimitive>(breaks: &[f64], data: &[T]) -> Result<f64, ClassifyError> {
+    gvf_sorted(breaks, &SortedData::try_new(data)?)
+}
+
+/// Returns the goodness of variance fit (GVF) for a set of breaks on a SortedData object, which takes O(k log n) time for k bins and n data points thanks to the SortedData's prefix sums
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a SortedData object built from the data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::{metrics, SortedData};
+///
+/// let sorted = SortedData::new(&[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]);
+///
+/// assert_eq!(metrics::gvf_sorted(&[4.0, 7.0], &sorted), Ok(0.96));
+/// assert_eq!(metrics::gvf_sorted(&[2.0, 5.0, 7.0], &sorted), Ok(0.9333333333333333));
+/// ```
+pub fn gvf_sorted(breaks: &[f64], data: &SortedData) -> Result<f64, ClassifyError> {
+    let sdcm: f64 = class_sdam_sorted(breaks, data)?.iter().sum();
+    Ok(ratio_fit(sdcm, data.ssd(0, data.num_vals())))
+}
+
+/// Returns the tabular accuracy index (TAI) for a set of breaks on a dataset, which is one minus the ratio of the sum of absolute deviations around class means to the sum of absolute deviations around the dataset mean
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::metrics;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(metrics::tai(&[4.0, 7.0], &data), Ok(0.7692307692307692));
+/// ```
+pub fn tai<T: ToPrimitive>(breaks: &[f64], data: &[T]) -> Result<f64, ClassifyError> {
+    Ok(evaluate(breaks, data)?.tai)
+}
+
+/// Returns the sum of absolute deviations around class medians (ADCM) for a set of breaks on a dataset
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::metrics;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(metrics::adcm(&[4.0, 7.0], &data), Ok(3.0));
+/// ```
+pub fn adcm<T: ToPrimitive>(breaks: &[f64], data: &[T]) -> Result<f64, ClassifyError> {
+    Ok(evaluate(breaks, data)?.adcm)
+}
+
+/// Returns the goodness of absolute deviation fit (GADF) for a set of breaks on a dataset, which is one minus the ratio of the ADCM to the sum of absolute deviations around the dataset median
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::metrics;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(metrics::gadf(&[4.0, 7.0], &data), Ok(0.7692307692307692));
+/// ```
+pub fn gadf<T: ToPrimitive>(breaks: &[f64], data: &[T]) -> Result<f64, ClassifyError> {
+    Ok(evaluate(breaks, data)?.gadf)
+}
+
+/// Returns the sum of squared deviations of each class around its own mean (the SDAM of each class treated as its own array) for a set of breaks on a dataset
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::metrics;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(metrics::class_sdam(&[4.0], &data), Ok(vec![0.5, 10.0]));
+/// ```
+pub fn class_sdam<T: ToPrimitive>(breaks: &[f64], data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+    class_sdam_sorted(breaks, &SortedData::try_new(data)?)
+}
+
+/// Returns the sum of squared deviations of each class around its own mean for a set of breaks on a SortedData object, which takes O(k log n) time for k bins and n data points thanks to the SortedData's prefix sums
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a SortedData object built from the data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::{metrics, SortedData};
+///
+/// let sorted = SortedData::new(&[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]);
+///
+/// assert_eq!(metrics::class_sdam_sorted(&[4.0], &sorted), Ok(vec![0.5, 10.0]));
+/// ```
+pub fn class_sdam_sorted(breaks: &[f64], data: &SortedData) -> Result<Vec<f64>, ClassifyError> {
+    Ok(class_ranges(breaks, data.values(), Closure::LeftClosed)?
+        .into_iter()
+        .map(|(lower, upper)| data.ssd(lower, upper))
+        .collect())
+}
+
+/// Returns the Jenks-Caspall (tabular) error for a set of breaks on a dataset, which is the sum of absolute deviations around class means
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending)
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::metrics;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// assert_eq!(metrics::jenks_caspall_error(&[4.0, 7.0], &data), Ok(3.0));
+/// ```
+pub fn jenks_caspall_error<T: ToPrimitive>(
+    breaks: &[f64],
+    data: &[T],
+) -> Result<f64, ClassifyError> {
+    Ok(evaluate(breaks, data)?.jenks_caspall_error)
+}
+
+/// Returns one minus the ratio of a within-class error to a global error, treating a dataset without any global error as a perfect fit
+fn ratio_fit(class_error: f64, global_error: f64) -> f64 {
+    if global_error > 0.0 {
+        1.0 - class_error / global_error
+    } else {
+        1.0
+    }
+}
+
+/// Translates and sorts a dataset, returning an error if it is empty or contains invalid values
+fn sort_data<T: ToPrimitive>(data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+    let mut sorted_data = try_to_vec_f64(data)?;
+    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Ok(sorted_data)
+}
+
+/// Returns the (lower inclusive, upper exclusive) index range of each class within sorted data given a set of breaks and the closure of the classes
+fn class_ranges(
+    breaks: &[f64],
+    sorted_data: &[f64],
+    closure: Closure,
+) -> Result<Vec<(usize, usize)>, ClassifyError> {
+    if breaks.iter().any(|item| !item.is_finite()) {
+        return Err(ClassifyError::InvalidParameter(
+            "breaks must be finite numbers".to_string(),
+        ));
+    }
+    if breaks.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(ClassifyError::InvalidParameter(
+            "breaks must be sorted in ascending order".to_string(),
+        ));
+    }
+
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    let mut lower = 0;
+    for item in breaks {
+        let upper = match closure {
+            Closure::LeftClosed => sorted_data.partition_point(|val| val < item),
+            Closure::RightClosed => sorted_data.partition_point(|val| val <= item),
+        };
+        ranges.push((lower, upper));
+        lower = upper;
+    }
+    ranges.push((lower, sorted_data.len()));
+
+    Ok(ranges)
+}
+
+/// Calculates the median of a non-empty, sorted dataset
+fn calc_median(sorted_data: &[f64]) -> f64 {
+    let num_vals = sorted_data.len();
+    if num_vals % 2 == 1 {
+        sorted_data[num_vals / 2]
+    } else {
+        (sorted_data[num_vals / 2 - 1] + sorted_data[num_vals / 2]) / 2.0
+    }
+}
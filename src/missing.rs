@@ -0,0 +1,171 @@
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ClassifyError;
+use crate::utilities::try_to_weights;
+
+/// Represents how missing values (NaN, infinite values, values that can't be converted to f64 and optional nodata sentinels) are handled
+///
+/// The policy is honored by Classifier::breaks_with_policy, Classifier::classify_with_policy, Classifier::breaks_weighted_with_policy, Classifier::classify_weighted_with_policy, SortedData::try_new_with_policy, try_breaks_to_classification_with_policy, apply_missing_policy, apply_missing_policy_weighted and the _with_policy variant of every algorithm's try_get_*_breaks and try_get_*_classification function (e.g. try_get_jenks_breaks_with_policy), and the resulting Classification keeps it so that classify_val and classify_values treat missing values the same way; every other function behaves like MissingPolicy::Error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MissingPolicy {
+    /// Return a ClassifyError on the first missing value (the default)
+    Error,
+    /// Skip NaN, infinite and unconvertible values
+    Skip,
+    /// Skip NaN, infinite and unconvertible values, as well as any value equal to one of the given nodata sentinels (e.g. -9999)
+    Sentinels(Vec<f64>),
+    /// Treat missing values and the given nodata sentinels like Sentinels, but count them in a dedicated no-data bin appended to the bins of the resulting Classification, whose index classify_val and classify_values return for missing values
+    NoDataBin(Vec<f64>),
+}
+
+impl MissingPolicy {
+    /// Returns the nodata sentinels of this policy (empty unless it is Sentinels or NoDataBin)
+    pub fn sentinels(&self) -> &[f64] {
+        match self {
+            MissingPolicy::Sentinels(sentinels) | MissingPolicy::NoDataBin(sentinels) => sentinels,
+            _ => &[],
+        }
+    }
+
+    /// Returns true if a value (None if it couldn't be converted to f64) is missing under this policy, meaning it is NaN, infinite, unconvertible or equal to one of the policy's sentinels
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::MissingPolicy;
+    ///
+    /// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+    ///
+    /// assert!(policy.is_missing(Some(-9999.0)) && policy.is_missing(Some(f64::NAN)) && policy.is_missing(None));
+    /// assert!(!policy.is_missing(Some(3.0)));
+    /// ```
+    pub fn is_missing(&self, val: Option<f64>) -> bool {
+        match val {
+            Some(val) => !val.is_finite() || self.sentinels().contains(&val),
+            None => true,
+        }
+    }
+}
+
+impl Default for MissingPolicy {
+    fn default() -> Self {
+        MissingPolicy::Error
+    }
+}
+
+/// Returns the values of a dataset that aren't missing according to a MissingPolicy (as f64), along with the number of values that were skipped
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of unsorted data points
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and whether they cause an error
+///
+/// # Edge Cases
+///
+/// * Returns ClassifyError::EmptyInput if no values remain after skipping missing values
+///
+/// # Examples
+///
+/// ```
+/// use classify::{apply_missing_policy, ClassifyError, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, -9999.0, f64::NAN, 4.0];
+///
+/// let result = apply_missing_policy(&data, &MissingPolicy::Sentinels(vec![-9999.0]));
+///
+/// assert_eq!(result, Ok((vec![1.0, 4.0], 2)));
+/// assert_eq!(apply_missing_policy(&data, &MissingPolicy::Error), Err(ClassifyError::NonFiniteValue(2)));
+/// ```
+pub fn apply_missing_policy<T: ToPrimitive>(
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<(Vec<f64>, usize), ClassifyError> {
+    let sentinels = policy.sentinels();
+
+    let mut values: Vec<f64> = Vec::with_capacity(data.len());
+    let mut num_skipped: usize = 0;
+    for (i, item) in data.iter().enumerate() {
+        let val = match item.to_f64() {
+            Some(val) => val,
+            None if *policy == MissingPolicy::Error => {
+                return Err(ClassifyError::ConversionFailed(i))
+            }
+            None => {
+                num_skipped += 1;
+                continue;
+            }
+        };
+        if !val.is_finite() {
+            if *policy == MissingPolicy::Error {
+                return Err(ClassifyError::NonFiniteValue(i));
+            }
+            num_skipped += 1;
+        } else if sentinels.contains(&val) {
+            num_skipped += 1;
+        } else {
+            values.push(val);
+        }
+    }
+
+    if values.is_empty() {
+        return Err(ClassifyError::EmptyInput);
+    }
+    Ok((values, num_skipped))
+}
+
+/// Returns the values of a weighted dataset that aren't missing according to a MissingPolicy (as f64) along with their weights, as well as the number of values that were skipped, or a ClassifyError if the weights are invalid
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of unsorted data points
+/// * `weights` - A reference to a collection of non-negative weights, one per data point
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and whether they cause an error
+///
+/// # Edge Cases
+///
+/// * The weights of skipped values are dropped along with them
+/// * Returns ClassifyError::EmptyInput if no values remain after skipping missing values
+///
+/// # Examples
+///
+/// ```
+/// use classify::{apply_missing_policy_weighted, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, -9999.0, f64::NAN, 4.0];
+/// let weights: Vec<f64> = vec![2.0, 1.0, 1.0, 3.0];
+///
+/// let result = apply_missing_policy_weighted(&data, &weights, &MissingPolicy::Sentinels(vec![-9999.0]));
+///
+/// assert_eq!(result, Ok((vec![1.0, 4.0], vec![2.0, 3.0], 2)));
+/// ```
+pub fn apply_missing_policy_weighted<T: ToPrimitive, W: ToPrimitive>(
+    data: &[T],
+    weights: &[W],
+    policy: &MissingPolicy,
+) -> Result<(Vec<f64>, Vec<f64>, usize), ClassifyError> {
+    let weights = try_to_weights(weights, data.len())?;
+
+    let mut values: Vec<f64> = Vec::with_capacity(data.len());
+    let mut kept_weights: Vec<f64> = Vec::with_capacity(data.len());
+    for (i, item) in data.iter().enumerate() {
+        let val = item.to_f64();
+        if *policy == MissingPolicy::Error {
+            match val {
+                None => return Err(ClassifyError::ConversionFailed(i)),
+                Some(val) if !val.is_finite() => return Err(ClassifyError::NonFiniteValue(i)),
+                _ => {}
+            }
+        }
+        if let Some(val) = val.filter(|val| !policy.is_missing(Some(*val))) {
+            values.push(val);
+            kept_weights.push(weights[i]);
+        }
+    }
+
+    if values.is_empty() {
+        return Err(ClassifyError::EmptyInput);
+    }
+    let num_skipped = data.len() - values.len();
+    Ok((values, kept_weights, num_skipped))
+}
@@ -0,0 +1,23 @@
+//! Helpers for work that is split into independent items, which run on rayon's global thread pool when the `parallel` feature is enabled and serially otherwise
+//!
+//! Results are always returned in the order of the items, so enabling the feature never changes the output of any function
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Returns the results of applying a function to every item of a slice, in the order of the items
+pub(crate) fn map_slice<T, U, F>(items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
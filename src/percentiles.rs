@@ -0,0 +1,478 @@
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::classifier::{Classifier, Percentiles};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
+use crate::utilities::{try_to_vec_f64, try_to_weighted_vals};
+use crate::utilities::{Classification, WeightedVal};
+
+/// Represents a definition of the sample quantile, following the nine types of Hyndman and Fan (1996) as numbered in R's quantile()
+///
+/// The first three types are discontinuous (they return one of the data points or the average of two), while types 4 through 9 interpolate linearly between the two data points surrounding a position that depends on the type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuantileDefinition {
+    /// Inverse of the empirical distribution function, also known as the nearest-rank method
+    Type1,
+    /// Inverse of the empirical distribution function with averaging at discontinuities
+    Type2,
+    /// The nearest even order statistic (SAS definition 2)
+    Type3,
+    /// Linear interpolation of the empirical distribution function
+    Type4,
+    /// Piecewise linear function whose knots are the midpoints of the steps of the empirical distribution function (Hazen)
+    Type5,
+    /// Linear interpolation where the kth data point is the k / (n + 1) quantile (Weibull, Excel's PERCENTILE.EXC)
+    Type6,
+    /// Linear interpolation where the kth data point is the (k - 1) / (n - 1) quantile (the default of R, NumPy and Excel's PERCENTILE.INC)
+    Type7,
+    /// Approximately median-unbiased regardless of the distribution of the data
+    Type8,
+    /// Approximately unbiased if the data is normally distributed
+    Type9,
+    /// The midpoint of the two data points surrounding the position n * p, which is the definition originally used by Quantile Breaks
+    Midpoint,
+}
+
+impl Default for QuantileDefinition {
+    fn default() -> Self {
+        QuantileDefinition::Midpoint
+    }
+}
+
+/// Returns a Classification object following Percentile Breaks given a list of percentiles and one-dimensional data
+///
+/// # Arguments
+///
+/// * `percentiles` - A reference to a collection of percentiles (between 0 and 100) at which to place breaks (e.g. [1, 10, 50, 90, 99])
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * Percentiles are calculated with QuantileDefinition::Type7; use the Percentiles classifier to choose another definition
+/// * Panics on empty data, non-finite data or percentiles outside of 0 to 100; use try_get_percentile_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_percentile_classification;
+/// use classify::{Classification, Bin};
+///
+/// let data: Vec<f64> = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+///
+/// let result: Classification = get_percentile_classification(&[10.0, 50.0, 90.0], &data);
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(0.0, 1.0, 1),
+///     Bin::new(1.0, 5.0, 4),
+///     Bin::new(5.0, 9.0, 4),
+///     Bin::new(9.0, 10.0, 2)
+/// ];
+///
+/// assert!(result == expected);
+/// ```
+pub fn get_percentile_classification<T: ToPrimitive>(
+    percentiles: &[f64],
+    data: &[T],
+) -> Classification {
+    try_get_percentile_classification(percentiles, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following Percentile Breaks given a list of percentiles and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `percentiles` - A reference to a collection of percentiles (between 0 and 100) at which to place breaks (e.g. [1, 10, 50, 90, 99])
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_percentile_classification;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0];
+///
+/// assert!(try_get_percentile_classification(&[50.0, 120.0], &data).is_err());
+/// ```
+pub fn try_get_percentile_classification<T: ToPrimitive>(
+    percentiles: &[f64],
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    Percentiles {
+        percentiles: percentiles.to_vec(),
+        definition: QuantileDefinition::Type7,
+    }
+    .classify(data)
+}
+
+/// Returns a Classification object following Percentile Breaks given a list of percentiles and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `percentiles` - A reference to a collection of percentiles (between 0 and 100) at which to place breaks (e.g. [1, 10, 50, 90, 99])
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_percentile_classification_with_policy<T: ToPrimitive>(
+    percentiles: &[f64],
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    Percentiles {
+        percentiles: percentiles.to_vec(),
+        definition: QuantileDefinition::Type7,
+    }
+    .classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks following Percentile Breaks given a list of percentiles and a dataset
+///
+/// # Arguments
+///
+/// * `percentiles` - A reference to a collection of percentiles (between 0 and 100) at which to place breaks (e.g. [1, 10, 50, 90, 99])
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * Percentiles are calculated with QuantileDefinition::Type7; use try_get_percentile_breaks_with_definition to choose another definition
+/// * Duplicate breaks and breaks at or outside the minimum and maximum of the data are dropped
+/// * Panics on empty data, non-finite data or percentiles outside of 0 to 100; use try_get_percentile_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_percentile_breaks;
+///
+/// let data: Vec<i32> = vec![0, 1, 2, 3, 4, 5, 6, 7, 8];
+///
+/// let result: Vec<f64> = get_percentile_breaks(&[90.0, 10.0, 25.0], &data);
+///
+/// assert_eq!(result, vec![0.8, 2.0, 7.2]);
+/// ```
+pub fn get_percentile_breaks<T: ToPrimitive>(percentiles: &[f64], data: &[T]) -> Vec<f64> {
+    try_get_percentile_breaks(percentiles, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks following Percentile Breaks given a list of percentiles and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `percentiles` - A reference to a collection of percentiles (between 0 and 100) at which to place breaks (e.g. [1, 10, 50, 90, 99])
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_percentile_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+///
+/// assert_eq!(try_get_percentile_breaks(&[0.0, 50.0, 100.0], &data), Ok(vec![3.0]));
+/// assert_eq!(try_get_percentile_breaks(&[50.0], &Vec::<f64>::new()), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_percentile_breaks<T: ToPrimitive>(
+    percentiles: &[f64],
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    try_get_percentile_breaks_with_definition(percentiles, QuantileDefinition::Type7, data)
+}
+
+/// Returns a vector of breaks following Percentile Breaks given a list of percentiles and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `percentiles` - A reference to a collection of percentiles (between 0 and 100) at which to place breaks (e.g. [1, 10, 50, 90, 99])
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_percentile_breaks, try_get_percentile_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_percentile_breaks_with_policy(&[25.0, 75.0], &data, &policy), try_get_percentile_breaks(&[25.0, 75.0], &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_percentile_breaks_with_policy(&[25.0, 75.0], &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_percentile_breaks_with_policy<T: ToPrimitive>(
+    percentiles: &[f64],
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    Percentiles {
+        percentiles: percentiles.to_vec(),
+        definition: QuantileDefinition::Type7,
+    }
+    .breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks following Percentile Breaks given a list of percentiles, the quantile definition to use and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `percentiles` - A reference to a collection of percentiles (between 0 and 100) at which to place breaks (e.g. [1, 10, 50, 90, 99])
+/// * `definition` - The definition of the sample quantile to use
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_percentile_breaks_with_definition, QuantileDefinition};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+///
+/// assert_eq!(try_get_percentile_breaks_with_definition(&[25.0], QuantileDefinition::Type1, &data), Ok(vec![3.0]));
+/// assert_eq!(try_get_percentile_breaks_with_definition(&[25.0], QuantileDefinition::Type6, &data), Ok(vec![2.75]));
+/// assert_eq!(try_get_percentile_breaks_with_definition(&[25.0], QuantileDefinition::Type7, &data), Ok(vec![3.25]));
+/// ```
+pub fn try_get_percentile_breaks_with_definition<T: ToPrimitive>(
+    percentiles: &[f64],
+    definition: QuantileDefinition,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    Percentiles {
+        percentiles: percentiles.to_vec(),
+        definition,
+    }
+    .breaks(data)
+}
+
+/// Returns the value of every percentile in a list (in the same order) given the quantile definition to use and a dataset, sorting the data only once, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `percentiles` - A reference to a collection of percentiles (between 0 and 100)
+/// * `definition` - The definition of the sample quantile to use
+/// * `data` - A reference to a collection of unsorted data points
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_percentiles, QuantileDefinition};
+///
+/// let data: Vec<f64> = vec![4.0, 1.0, 3.0, 2.0];
+///
+/// assert_eq!(try_get_percentiles(&[50.0, 0.0, 100.0], QuantileDefinition::Type7, &data), Ok(vec![2.5, 1.0, 4.0]));
+/// assert_eq!(try_get_percentiles(&[50.0], QuantileDefinition::Type1, &data), Ok(vec![2.0]));
+/// ```
+pub fn try_get_percentiles<T: ToPrimitive>(
+    percentiles: &[f64],
+    definition: QuantileDefinition,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    validate_percentiles(percentiles)?;
+    let mut sorted_data = try_to_vec_f64(data)?;
+    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    Ok(percentiles
+        .iter()
+        .map(|perc| calc_quantile(&sorted_data, *perc, 100.0, definition))
+        .collect())
+}
+
+/// Returns the breaks of a Percentiles configuration on a dataset, or a ClassifyError if any percentile is invalid
+pub(crate) fn calc_percentile_breaks<T: ToPrimitive>(
+    method: &Percentiles,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    validate_percentiles(&method.percentiles)?;
+
+    calc_percentile_breaks_sorted(method, &SortedData::try_new_without_sums(data)?)
+}
+
+/// Returns the breaks of a Percentiles configuration on a SortedData object, or a ClassifyError if any percentile is invalid
+pub(crate) fn calc_percentile_breaks_sorted(
+    method: &Percentiles,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    validate_percentiles(&method.percentiles)?;
+
+    Ok(select_percentile_breaks(
+        &method.percentiles,
+        (sorted.min(), sorted.max()),
+        &|perc| calc_quantile(sorted.values(), perc, 100.0, method.definition),
+    ))
+}
+
+/// Returns the breaks of a Percentiles configuration on a weighted dataset, or a ClassifyError if any percentile, data point or weight is invalid
+pub(crate) fn calc_percentile_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    method: &Percentiles,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    validate_percentiles(&method.percentiles)?;
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+    let cum_weights = calc_cumulative_weights(&weighted_vals);
+
+    let min_value = weighted_vals[0].val;
+    let max_value = weighted_vals[weighted_vals.len() - 1].val;
+    Ok(select_percentile_breaks(
+        &method.percentiles,
+        (min_value, max_value),
+        &|perc| {
+            calc_weighted_quantile(&weighted_vals, &cum_weights, perc, 100.0, method.definition)
+        },
+    ))
+}
+
+/// Returns the sorted, deduplicated values of a list of percentiles lying strictly within a range, given a function calculating the value of a percentile
+fn select_percentile_breaks(
+    percentiles: &[f64],
+    (min_value, max_value): (f64, f64),
+    calc_percentile: &dyn Fn(f64) -> f64,
+) -> Vec<f64> {
+    let mut percentiles = percentiles.to_vec();
+    percentiles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let mut breaks: Vec<f64> = vec![];
+    for perc in percentiles {
+        let val = calc_percentile(perc);
+        if min_value < val && val < max_value {
+            breaks.push(val);
+        }
+    }
+    breaks.dedup();
+    breaks
+}
+
+/// Returns a ClassifyError if any percentile isn't a finite number between 0 and 100
+fn validate_percentiles(percentiles: &[f64]) -> Result<(), ClassifyError> {
+    if percentiles
+        .iter()
+        .any(|perc| !perc.is_finite() || *perc < 0.0 || *perc > 100.0)
+    {
+        return Err(ClassifyError::InvalidParameter(
+            "percentiles must be finite numbers between 0 and 100".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Calculates the quantile of sorted data at the probability numerator / denominator following a quantile definition
+///
+/// The probability is passed as a fraction so that n * p is computed exactly whenever it is a whole number
+///
+/// # Arguments
+///
+/// * `sorted_data` - A reference to a non-empty collection of sorted data points
+/// * `numerator` - The numerator of the probability (e.g. a percentile)
+/// * `denominator` - The denominator of the probability (e.g. 100 for a percentile)
+/// * `definition` - The definition of the sample quantile to use
+pub(crate) fn calc_quantile(
+    sorted_data: &[f64],
+    numerator: f64,
+    denominator: f64,
+    definition: QuantileDefinition,
+) -> f64 {
+    let num_vals = sorted_data.len() as f64;
+    let order_stat = |k: f64| -> f64 { sorted_data[(k.max(1.0).min(num_vals) - 1.0) as usize] };
+    calc_quantile_by(num_vals, &order_stat, numerator, denominator, definition)
+}
+
+/// Calculates the quantile of weighted data at the probability numerator / denominator following a quantile definition, treating weights as numbers of occurrences
+///
+/// # Arguments
+///
+/// * `weighted_vals` - A reference to a non-empty collection of sorted unique values with positive weights
+/// * `cum_weights` - A reference to the cumulative weights of the unique values
+/// * `numerator` - The numerator of the probability (e.g. a percentile)
+/// * `denominator` - The denominator of the probability (e.g. 100 for a percentile)
+/// * `definition` - The definition of the sample quantile to use
+pub(crate) fn calc_weighted_quantile(
+    weighted_vals: &[WeightedVal],
+    cum_weights: &[f64],
+    numerator: f64,
+    denominator: f64,
+    definition: QuantileDefinition,
+) -> f64 {
+    let total_weight = cum_weights[cum_weights.len() - 1];
+    // The kth order statistic is the first unique value whose cumulative weight reaches k
+    let order_stat = |k: f64| -> f64 {
+        let idx = cum_weights.partition_point(|cum_weight| *cum_weight < k);
+        weighted_vals[idx.min(weighted_vals.len() - 1)].val
+    };
+    calc_quantile_by(
+        total_weight,
+        &order_stat,
+        numerator,
+        denominator,
+        definition,
+    )
+}
+
+/// Returns the running totals of the weights of a collection of weighted values
+pub(crate) fn calc_cumulative_weights(weighted_vals: &[WeightedVal]) -> Vec<f64> {
+    let mut total = 0.0;
+    weighted_vals
+        .iter()
+        .map(|item| {
+            total += item.weight;
+            total
+        })
+        .collect()
+}
+
+/// Calculates a quantile following a quantile definition given the (possibly weighted) number of data points and a function returning the kth order statistic (counting from 1, clamped to the range of the data)
+fn calc_quantile_by(
+    num_vals: f64,
+    order_stat: &dyn Fn(f64) -> f64,
+    numerator: f64,
+    denominator: f64,
+    definition: QuantileDefinition,
+) -> f64 {
+    let n_p = num_vals * numerator / denominator;
+    let j = n_p.floor();
+    match definition {
+        QuantileDefinition::Type1 => {
+            if n_p > j {
+                order_stat(j + 1.0)
+            } else {
+                order_stat(j)
+            }
+        }
+        QuantileDefinition::Type2 => {
+            if n_p > j {
+                order_stat(j + 1.0)
+            } else {
+                (order_stat(j) + order_stat(j + 1.0)) / 2.0
+            }
+        }
+        QuantileDefinition::Type3 => {
+            let position = n_p - 0.5;
+            let j = position.floor();
+            if position == j && j % 2.0 == 0.0 {
+                order_stat(j)
+            } else {
+                order_stat(j + 1.0)
+            }
+        }
+        QuantileDefinition::Midpoint => (order_stat(j) + order_stat(j + 1.0)) / 2.0,
+        _ => {
+            // The position of the quantile (counting from 0) is (n + 1 - alpha - beta) * p + alpha - 1
+            let (alpha, beta) = match definition {
+                QuantileDefinition::Type4 => (0.0, 1.0),
+                QuantileDefinition::Type5 => (0.5, 0.5),
+                QuantileDefinition::Type6 => (0.0, 0.0),
+                QuantileDefinition::Type7 => (1.0, 1.0),
+                QuantileDefinition::Type8 => (1.0 / 3.0, 1.0 / 3.0),
+                _ => (3.0 / 8.0, 3.0 / 8.0),
+            };
+            let position =
+                (num_vals + 1.0 - alpha - beta) * numerator / denominator + (alpha - 1.0);
+            if position <= 0.0 {
+                return order_stat(1.0);
+            }
+            if position >= num_vals - 1.0 {
+                return order_stat(num_vals);
+            }
+            let lower = position.floor();
+            let fraction = position - lower;
+            let lower_val = order_stat(lower + 1.0);
+            lower_val + fraction * (order_stat(lower + 2.0) - lower_val)
+        }
+    }
+}
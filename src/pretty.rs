@@ -0,0 +1,357 @@
+use num_traits::ToPrimitive;
+
+use crate::classifier::{Classifier, Pretty};
+use crate::error::ClassifyError;
+use crate::metrics;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
+use crate::utilities::try_to_vec_f64;
+use crate::utilities::Classification;
+
+/// The multipliers of powers of ten considered "nice" step sizes, in ascending order
+const NICE_MULTIPLIERS: [f64; 4] = [1.0, 2.0, 2.5, 5.0];
+
+/// Represents the outcome of snapping a set of breaks to nice numbers
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnappedBreaks {
+    /// The snapped breaks (sorted, ascending, without duplicates)
+    pub breaks: Vec<f64>,
+    /// The goodness of variance fit (GVF) of the original breaks on the data
+    pub gvf_before: f64,
+    /// The goodness of variance fit (GVF) of the snapped breaks on the data
+    pub gvf_after: f64,
+    /// The change in GVF caused by snapping (gvf_after - gvf_before, usually negative)
+    pub gvf_change: f64,
+}
+
+/// Returns a Classification object following the Pretty Breaks algorithm given the approximate desired number of bins and one-dimensional data
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the approximate desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The number of bins produced is only approximately num_bins
+/// * Panics on empty data, non-finite data or zero bins; use try_get_pretty_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_pretty_classification;
+/// use classify::{Classification, Bin};
+///
+/// let data: Vec<f64> = vec![1.0, 3.0, 4.5, 6.0, 9.5];
+///
+/// let result: Classification = get_pretty_classification(3, &data);
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(1.0, 5.0, 3),
+///     Bin::new(5.0, 9.5, 2)
+/// ];
+///
+/// assert!(result == expected);
+/// ```
+pub fn get_pretty_classification<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Classification {
+    try_get_pretty_classification(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Pretty Breaks algorithm given the approximate desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the approximate desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_pretty_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0];
+///
+/// assert_eq!(try_get_pretty_classification(0, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_pretty_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    Pretty { num_bins }.classify(data)
+}
+
+/// Returns a Classification object following the Pretty Breaks algorithm given the approximate desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the approximate desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_pretty_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    Pretty { num_bins }.classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Pretty Breaks algorithm given the approximate desired number of bins and a dataset
+///
+/// Similar to R's pretty(), the breaks are the multiples of a nice step size (1, 2, 2.5 or 5 times a power of ten) lying strictly within the range of the data, with the step size chosen so that the number of bins is as close as possible to num_bins
+///
+/// # Arguments
+///
+/// * `num_bins` - The approximate desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * The number of bins produced is only approximately num_bins; among equally close step sizes the largest is kept
+/// * If all data points are equal, no breaks are produced
+/// * Panics on empty data, non-finite data or zero bins; use try_get_pretty_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_pretty_breaks;
+///
+/// let data: Vec<i32> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+///
+/// let result: Vec<f64> = get_pretty_breaks(5, &data);
+///
+/// assert_eq!(result, vec![2.0, 4.0, 6.0, 8.0]);
+/// ```
+pub fn get_pretty_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64> {
+    try_get_pretty_breaks(num_bins, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Pretty Breaks algorithm given the approximate desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The approximate desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_pretty_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![0.013, 0.02, 0.071, 0.12];
+///
+/// assert_eq!(try_get_pretty_breaks(5, &data), Ok(vec![0.025, 0.05, 0.075, 0.1]));
+/// assert_eq!(try_get_pretty_breaks(5, &Vec::<f64>::new()), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_get_pretty_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let data = try_to_vec_f64(data)?;
+
+    let mut min_value = data[0];
+    let mut max_value = data[0];
+    for item in &data {
+        if *item < min_value {
+            min_value = *item;
+        } else if *item > max_value {
+            max_value = *item;
+        }
+    }
+
+    Ok(calc_pretty_breaks(num_bins, min_value, max_value))
+}
+
+/// Returns a vector of breaks generated through the Pretty Breaks algorithm given the approximate desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The approximate desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_pretty_breaks, try_get_pretty_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_pretty_breaks_with_policy(3, &data, &policy), try_get_pretty_breaks(3, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_pretty_breaks_with_policy(3, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_pretty_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    Pretty { num_bins }.breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Pretty Breaks algorithm given the desired number of bins and a SortedData object, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_pretty_breaks_sorted(
+    num_bins: usize,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+
+    Ok(calc_pretty_breaks(num_bins, sorted.min(), sorted.max()))
+}
+
+/// Returns the nice breaks between a minimum and a maximum whose number of bins is closest to the desired number of bins
+fn calc_pretty_breaks(num_bins: usize, min_value: f64, max_value: f64) -> Vec<f64> {
+    let range = max_value - min_value;
+    if range == 0.0 {
+        return vec![];
+    }
+
+    let exponent = (range / num_bins as f64).log10().floor() as i32;
+    let mut candidates: Vec<(f64, i32)> = vec![(1.0, exponent + 1)];
+    for multiplier in NICE_MULTIPLIERS.iter().rev() {
+        candidates.push((*multiplier, exponent));
+    }
+
+    let mut best: Option<(i64, i64, f64, i32)> = None;
+    let mut best_diff = usize::MAX;
+    for (multiplier, exp) in candidates {
+        let step = nice_number(1.0, multiplier, exp);
+        let first = (min_value / step).floor() as i64;
+        let last = (max_value / step).ceil() as i64;
+        let diff = ((last - first) as usize).abs_diff(num_bins);
+        if diff < best_diff {
+            best_diff = diff;
+            best = Some((first, last, multiplier, exp));
+        }
+    }
+
+    let (first, last, multiplier, exp) = best.unwrap();
+    let mut breaks: Vec<f64> = vec![];
+    for i in first..=last {
+        let val = nice_number(i as f64, multiplier, exp);
+        if min_value < val && val < max_value {
+            breaks.push(val);
+        }
+    }
+
+    breaks
+}
+
+/// Returns a set of breaks rounded to nice numbers (as few significant digits as possible, on multiples of 1, 2, 2.5 or 5 times a power of ten), each moving by at most a tolerance, along with the change in GVF this causes on a dataset
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending) generated through any classification function or manually
+/// * `tolerance` - The maximum distance by which any break may move (a tolerance of 0 leaves the breaks unchanged)
+/// * `data` - A reference to a collection of unsorted data points used to compute the GVF before and after snapping
+///
+/// # Edge Cases
+///
+/// * Breaks that snap to the same nice number are merged, so fewer breaks may be returned
+/// * Panics on empty data, non-finite data, invalid breaks or a negative or non-finite tolerance; use try_snap_breaks_to_nice to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::{get_st_dev_breaks, snap_breaks_to_nice};
+///
+/// let data: Vec<f64> = vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0];
+/// let breaks: Vec<f64> = get_st_dev_breaks(1.0, &data);
+///
+/// let result = snap_breaks_to_nice(&breaks, 0.1, &data);
+///
+/// assert_eq!(breaks, vec![0.41987655026535653, 1.5, 2.5801234497346437]);
+/// assert_eq!(result.breaks, vec![0.5, 1.5, 2.5]);
+/// assert_eq!(result.gvf_change, result.gvf_after - result.gvf_before);
+/// ```
+pub fn snap_breaks_to_nice<T: ToPrimitive>(
+    breaks: &[f64],
+    tolerance: f64,
+    data: &[T],
+) -> SnappedBreaks {
+    try_snap_breaks_to_nice(breaks, tolerance, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a set of breaks rounded to nice numbers, each moving by at most a tolerance, along with the change in GVF this causes on a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a collection of breaks (sorted, ascending) generated through any classification function or manually
+/// * `tolerance` - The maximum distance by which any break may move (a tolerance of 0 leaves the breaks unchanged)
+/// * `data` - A reference to a collection of unsorted data points used to compute the GVF before and after snapping
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_snap_breaks_to_nice;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0];
+/// let breaks: Vec<f64> = vec![4.333333333333333, 8.666666666666666];
+///
+/// let result = try_snap_breaks_to_nice(&breaks, 0.5, &data).unwrap();
+///
+/// assert_eq!(result.breaks, vec![4.0, 9.0]);
+/// assert!(result.gvf_change.abs() < 0.01);
+/// assert!(try_snap_breaks_to_nice(&breaks, -1.0, &data).is_err());
+/// ```
+pub fn try_snap_breaks_to_nice<T: ToPrimitive>(
+    breaks: &[f64],
+    tolerance: f64,
+    data: &[T],
+) -> Result<SnappedBreaks, ClassifyError> {
+    if !tolerance.is_finite() || tolerance < 0.0 {
+        return Err(ClassifyError::InvalidParameter(
+            "tolerance must be a finite, non-negative number".to_string(),
+        ));
+    }
+    let gvf_before = metrics::gvf(breaks, data)?;
+
+    let mut snapped: Vec<f64> = vec![];
+    for item in breaks {
+        snapped.push(snap_to_nice(*item, tolerance));
+    }
+    snapped.dedup();
+
+    let gvf_after = metrics::gvf(&snapped, data)?;
+    Ok(SnappedBreaks {
+        breaks: snapped,
+        gvf_before,
+        gvf_after,
+        gvf_change: gvf_after - gvf_before,
+    })
+}
+
+/// Returns the roundest nice number within a tolerance of a value, trying step sizes from coarsest to finest
+fn snap_to_nice(val: f64, tolerance: f64) -> f64 {
+    if tolerance == 0.0 {
+        return val;
+    }
+    let mut exp = val.abs().max(tolerance).log10().floor() as i32 + 1;
+    loop {
+        for multiplier in NICE_MULTIPLIERS.iter().rev() {
+            let step = nice_number(1.0, *multiplier, exp);
+            let snapped = nice_number((val / step).round(), *multiplier, exp);
+            if (snapped - val).abs() <= tolerance {
+                return snapped;
+            }
+        }
+        exp -= 1;
+    }
+}
+
+/// Returns count * multiplier * 10^exp, dividing by a power of ten for negative exponents so that decimal values are represented as closely as possible
+fn nice_number(count: f64, multiplier: f64, exp: i32) -> f64 {
+    if exp >= 0 {
+        count * multiplier * 10_f64.powi(exp)
+    } else {
+        count * multiplier / 10_f64.powi(-exp)
+    }
+}
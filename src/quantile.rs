@@ -1,5 +1,11 @@
-use crate::utilities::Classification;
-use crate::utilities::{breaks_to_classification, to_vec_f64};
+use crate::classifier::{Classifier, Quantile};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::percentiles::QuantileDefinition;
+use crate::percentiles::{calc_cumulative_weights, calc_quantile, calc_weighted_quantile};
+use crate::sorted_data::SortedData;
+use crate::utilities::try_to_weighted_vals;
+use crate::utilities::{Classification, WeightedVal};
 use num_traits::ToPrimitive;
 
 /// Returns a Classification object following the Quantile Breaks algorithm given the desired number of bins and one-dimensional data
@@ -14,6 +20,8 @@ use num_traits::ToPrimitive;
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
 /// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
 /// * If your dataset contains many duplicates, there is a chance that the number of bins produced by the algorithm differs from num_bins because duplicate breaks are removed
+/// * Zero bins are treated as one bin (no breaks), as in earlier versions; try_get_quantile_classification returns ClassifyError::InvalidBinCount instead
+/// * Panics on empty data or non-finite data; use try_get_quantile_classification to handle these cases instead
 ///
 /// # Examples
 ///
@@ -25,17 +33,63 @@ use num_traits::ToPrimitive;
 /// let num_bins = 3;
 ///
 /// let result: Classification = get_quantile_classification(num_bins, &data);
-/// let expected: Classification = vec![
-///     Bin{bin_start: 1.0, bin_end: 3.5, count: 3},
-///     Bin{bin_start: 3.5, bin_end: 6.5, count: 3},
-///     Bin{bin_start: 6.5, bin_end: 9.0, count: 3}
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(1.0, 3.5, 3),
+///     Bin::new(3.5, 6.5, 3),
+///     Bin::new(6.5, 9.0, 3)
 /// ];
 ///
 /// assert!(result == expected);
 /// ```
 pub fn get_quantile_classification<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Classification {
-    let breaks: Vec<f64> = get_quantile_breaks(num_bins, data);
-    breaks_to_classification(&breaks, data)
+    try_get_quantile_classification(num_bins.max(1), data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Quantile Breaks algorithm given the desired number of bins and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_quantile_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN];
+///
+/// assert_eq!(try_get_quantile_classification(2, &data), Err(ClassifyError::NonFiniteValue(2)));
+/// ```
+pub fn try_get_quantile_classification<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    Quantile {
+        num_bins,
+        definition: QuantileDefinition::default(),
+    }
+    .classify(data)
+}
+
+/// Returns a Classification object following the Quantile Breaks algorithm given the desired number of bins and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - An integer (usize) representing the desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_quantile_classification_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    Quantile {
+        num_bins,
+        definition: QuantileDefinition::default(),
+    }
+    .classify_with_policy(data, policy)
 }
 
 /// Returns a vector of breaks generated through the Quantile Breaks algorithm given the desired number of bins and a dataset
@@ -50,6 +104,8 @@ pub fn get_quantile_classification<T: ToPrimitive>(num_bins: usize, data: &[T])
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
 /// * The maximum number of bins generated by this algorithm is the number of unique values in the dataset
 /// * If your dataset contains many duplicates, there is a chance that the number of bins produced by the algorithm differs from num_bins because duplicate breaks are removed
+/// * Zero bins are treated as one bin (no breaks), as in earlier versions; try_get_quantile_breaks returns ClassifyError::InvalidBinCount instead
+/// * Panics on empty data or non-finite data; use try_get_quantile_breaks to handle these cases instead
 ///
 /// # Examples
 ///
@@ -64,32 +120,166 @@ pub fn get_quantile_classification<T: ToPrimitive>(num_bins: usize, data: &[T])
 /// assert_eq!(result, vec![3.5, 6.5]);
 /// ```
 pub fn get_quantile_breaks<T: ToPrimitive>(num_bins: usize, data: &[T]) -> Vec<f64> {
-    let data = to_vec_f64(data);
+    try_get_quantile_breaks(num_bins.max(1), data).unwrap_or_else(|err| panic!("{}", err))
+}
 
-    if num_bins == 0 || num_bins == 1 {
-        let result: Vec<f64> = vec![];
-        return result;
+/// Returns a vector of breaks generated through the Quantile Breaks algorithm given the desired number of bins and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_quantile_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<i32> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+///
+/// assert_eq!(try_get_quantile_breaks(3, &data), Ok(vec![3.5, 6.5]));
+/// assert_eq!(try_get_quantile_breaks(0, &data), Err(ClassifyError::InvalidBinCount(0)));
+/// ```
+pub fn try_get_quantile_breaks<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    try_get_quantile_breaks_with_definition(num_bins, QuantileDefinition::default(), data)
+}
+
+/// Returns a vector of breaks generated through the Quantile Breaks algorithm given the desired number of bins and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_quantile_breaks, try_get_quantile_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_quantile_breaks_with_policy(3, &data, &policy), try_get_quantile_breaks(3, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_quantile_breaks_with_policy(3, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_quantile_breaks_with_policy<T: ToPrimitive>(
+    num_bins: usize,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    Quantile {
+        num_bins,
+        definition: QuantileDefinition::default(),
     }
+    .breaks_with_policy(data, policy)
+}
 
-    let num_vals = data.len();
+/// Returns a vector of breaks generated through the Quantile Breaks algorithm given the desired number of bins, the quantile definition to use and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `num_bins` - The desired number of bins
+/// * `definition` - The definition of the sample quantile used to place the breaks (try_get_quantile_breaks uses QuantileDefinition::Midpoint)
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_quantile_breaks_with_definition, QuantileDefinition};
+///
+/// let data: Vec<i32> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+///
+/// assert_eq!(try_get_quantile_breaks_with_definition(4, QuantileDefinition::Midpoint, &data), Ok(vec![2.5, 5.5, 7.5]));
+/// assert_eq!(try_get_quantile_breaks_with_definition(4, QuantileDefinition::Type7, &data), Ok(vec![3.25, 5.5, 7.75]));
+/// ```
+pub fn try_get_quantile_breaks_with_definition<T: ToPrimitive>(
+    num_bins: usize,
+    definition: QuantileDefinition,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    try_get_quantile_breaks_sorted(
+        num_bins,
+        definition,
+        &SortedData::try_new_without_sums(data)?,
+    )
+}
 
-    let mut sorted_data: Vec<f64> = vec![];
-    for item in data.iter().take(num_vals) {
-        sorted_data.push(*item);
+/// Returns a vector of breaks generated through the Quantile Breaks algorithm given the desired number of bins, the quantile definition to use and a SortedData object, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_quantile_breaks_sorted(
+    num_bins: usize,
+    definition: QuantileDefinition,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    if num_bins == 1 {
+        let result: Vec<f64> = vec![];
+        return Ok(result);
     }
-    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    println!("{:#?}", sorted_data);
 
-    let true_num_bins = std::cmp::min(num_vals, num_bins);
+    let sorted_data = sorted.values();
+    let true_num_bins = std::cmp::min(sorted_data.len(), num_bins);
 
     let mut breaks: Vec<f64> = vec![];
-
     for i in 1..true_num_bins {
-        let new_break = (sorted_data
-            [((i * num_vals) as f64 / (true_num_bins) as f64) as usize - 1]
-            + sorted_data[((i * num_vals) as f64 / (true_num_bins) as f64) as usize])
-            / 2.0;
-        breaks.push(new_break);
+        breaks.push(calc_quantile(
+            sorted_data,
+            i as f64,
+            true_num_bins as f64,
+            definition,
+        ));
+    }
+
+    breaks.dedup();
+
+    Ok(breaks)
+}
+
+/// Returns a vector of breaks generated through the Quantile Breaks algorithm given the desired number of bins, the quantile definition to use and a weighted dataset, so that every bin holds about the same total weight, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_quantile_breaks_weighted<T: ToPrimitive, W: ToPrimitive>(
+    num_bins: usize,
+    definition: QuantileDefinition,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    if num_bins == 0 {
+        return Err(ClassifyError::InvalidBinCount(num_bins));
+    }
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+
+    Ok(calc_weighted_quantile_breaks(
+        &weighted_vals,
+        num_bins,
+        definition,
+    ))
+}
+
+/// Returns the breaks dividing weighted unique values into bins holding about the same total weight given the desired number of bins (at least one) and the quantile definition to use
+pub(crate) fn calc_weighted_quantile_breaks(
+    weighted_vals: &[WeightedVal],
+    num_bins: usize,
+    definition: QuantileDefinition,
+) -> Vec<f64> {
+    let cum_weights = calc_cumulative_weights(weighted_vals);
+
+    let mut breaks: Vec<f64> = vec![];
+    for i in 1..num_bins {
+        breaks.push(calc_weighted_quantile(
+            weighted_vals,
+            &cum_weights,
+            i as f64,
+            num_bins as f64,
+            definition,
+        ));
     }
 
     breaks.dedup();
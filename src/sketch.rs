@@ -0,0 +1,355 @@
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ClassifyError;
+use crate::utilities::try_to_vec_f64;
+
+/// Represents a streaming, mergeable summary of a dataset (a KLL sketch) from which approximate breaks can be computed without holding the whole dataset in memory
+///
+/// Values are added chunk by chunk and sketches built on separate partitions of a dataset can be merged; the sketch retains O(k log(n / k)) weighted samples and the rank of any value is estimated to within about `rank_error()` of the true rank (as a fraction of the number of values)
+///
+/// Breaks are computed from a sketch through Classifier::breaks_from_sketch, which treats the retained samples as weighted data
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, Quantile, QuantileSketch};
+///
+/// let mut left = QuantileSketch::default();
+/// let mut right = QuantileSketch::default();
+/// for chunk in (0..50000).collect::<Vec<u32>>().chunks(1000) {
+///     left.try_extend(chunk).unwrap();
+/// }
+/// right.try_extend(&(50000..100000).collect::<Vec<u32>>()).unwrap();
+/// left.merge(&right);
+///
+/// assert_eq!(left.num_vals(), 100000);
+/// assert_eq!((left.min(), left.max()), (Some(0.0), Some(99999.0)));
+///
+/// let method = Quantile { num_bins: 4, definition: Default::default() };
+/// let breaks = method.breaks_from_sketch(&left).unwrap();
+/// for (item, expected) in breaks.iter().zip([25000.0, 50000.0, 75000.0]) {
+///     assert!((item - expected).abs() < 100000.0 * left.rank_error());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawQuantileSketch")]
+pub struct QuantileSketch {
+    k: usize,
+    levels: Vec<Vec<f64>>,
+    num_vals: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    coin: u64,
+}
+
+/// Represents the serialized fields of a QuantileSketch before they are validated
+#[derive(Deserialize)]
+struct RawQuantileSketch {
+    k: usize,
+    levels: Vec<Vec<f64>>,
+    num_vals: u64,
+    min: Option<f64>,
+    max: Option<f64>,
+    coin: u64,
+}
+
+impl TryFrom<RawQuantileSketch> for QuantileSketch {
+    type Error = ClassifyError;
+
+    /// Returns the QuantileSketch made of deserialized fields, or a ClassifyError if they don't describe a valid sketch
+    fn try_from(raw: RawQuantileSketch) -> Result<Self, ClassifyError> {
+        let invalid =
+            |reason: &str| ClassifyError::InvalidParameter(format!("invalid sketch: {}", reason));
+        if raw.k < 8 {
+            return Err(invalid("k must be at least 8"));
+        }
+        if raw.levels.is_empty() || raw.levels.len() > 64 {
+            return Err(invalid("there must be between 1 and 64 levels"));
+        }
+        if raw.levels.iter().flatten().any(|item| !item.is_finite()) {
+            return Err(invalid("samples must be finite"));
+        }
+        let total_weight =
+            raw.levels
+                .iter()
+                .enumerate()
+                .try_fold(0_u64, |total, (height, level)| {
+                    (level.len() as u64)
+                        .checked_mul(1_u64 << height)
+                        .and_then(|weight| total.checked_add(weight))
+                });
+        if total_weight != Some(raw.num_vals) {
+            return Err(invalid(
+                "the weights of the samples must add up to num_vals",
+            ));
+        }
+        match (raw.min, raw.max) {
+            (None, None) if raw.num_vals == 0 => {}
+            (Some(min), Some(max))
+                if raw.num_vals > 0 && min.is_finite() && min <= max && max.is_finite() => {}
+            _ => {
+                return Err(invalid(
+                    "min and max must be set (and finite) exactly when the sketch isn't empty",
+                ))
+            }
+        }
+        Ok(QuantileSketch {
+            k: raw.k,
+            levels: raw.levels,
+            num_vals: raw.num_vals,
+            min: raw.min,
+            max: raw.max,
+            coin: raw.coin,
+        })
+    }
+}
+
+impl Default for QuantileSketch {
+    fn default() -> Self {
+        QuantileSketch::new(200)
+    }
+}
+
+impl QuantileSketch {
+    /// Returns an empty QuantileSketch given its accuracy parameter k (200 by default), the number of samples kept at its top level; larger values of k lower the rank error at the cost of memory
+    ///
+    /// # Edge Cases
+    ///
+    /// * Panics if k is less than 8; use QuantileSketch::try_new to handle this case instead
+    pub fn new(k: usize) -> Self {
+        QuantileSketch::try_new(k).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Returns an empty QuantileSketch given its accuracy parameter k, or a ClassifyError if k is less than 8
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{ClassifyError, QuantileSketch};
+    ///
+    /// assert_eq!(QuantileSketch::try_new(400).unwrap().k(), 400);
+    /// assert!(matches!(QuantileSketch::try_new(4), Err(ClassifyError::InvalidParameter(_))));
+    /// ```
+    pub fn try_new(k: usize) -> Result<Self, ClassifyError> {
+        if k < 8 {
+            return Err(ClassifyError::InvalidParameter(
+                "k must be at least 8".to_string(),
+            ));
+        }
+        Ok(QuantileSketch {
+            k,
+            levels: vec![vec![]],
+            num_vals: 0,
+            min: None,
+            max: None,
+            coin: 0x9E37_79B9_7F4A_7C15,
+        })
+    }
+
+    /// Adds a single value to the sketch, or returns a ClassifyError (indexed by the number of values added so far) if it can't be converted or isn't finite
+    pub fn try_insert<T: ToPrimitive>(&mut self, val: T) -> Result<(), ClassifyError> {
+        let index = self.num_vals as usize;
+        let val = val.to_f64().ok_or(ClassifyError::ConversionFailed(index))?;
+        if !val.is_finite() {
+            return Err(ClassifyError::NonFiniteValue(index));
+        }
+        self.push(val);
+        Ok(())
+    }
+
+    /// Adds a chunk of values to the sketch, or returns a ClassifyError (indexed within the chunk) if any value can't be converted or isn't finite, in which case the sketch is left unchanged
+    pub fn try_extend<T: ToPrimitive>(&mut self, data: &[T]) -> Result<(), ClassifyError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        for val in try_to_vec_f64(data)? {
+            self.push(val);
+        }
+        Ok(())
+    }
+
+    /// Merges another sketch into this one, as if every value added to it had been added to this sketch; the merged sketch keeps the smaller of the two accuracy parameters
+    pub fn merge(&mut self, other: &QuantileSketch) {
+        self.k = std::cmp::min(self.k, other.k);
+        while self.levels.len() < other.levels.len() {
+            self.levels.push(vec![]);
+        }
+        for (level, other_level) in self.levels.iter_mut().zip(other.levels.iter()) {
+            level.extend_from_slice(other_level);
+        }
+        self.num_vals += other.num_vals;
+        self.min = combine(self.min, other.min, f64::min);
+        self.max = combine(self.max, other.max, f64::max);
+        self.compress();
+    }
+
+    /// Returns the accuracy parameter k of the sketch
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the number of values added to the sketch (including those added to merged sketches)
+    pub fn num_vals(&self) -> u64 {
+        self.num_vals
+    }
+
+    /// Returns true if no values have been added to the sketch
+    pub fn is_empty(&self) -> bool {
+        self.num_vals == 0
+    }
+
+    /// Returns the number of samples currently retained by the sketch
+    pub fn num_retained(&self) -> usize {
+        self.levels.iter().map(|level| level.len()).sum()
+    }
+
+    /// Returns the exact minimum of the values added to the sketch, or None if it is empty
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    /// Returns the exact maximum of the values added to the sketch, or None if it is empty
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    /// Returns the approximate bound (with 99% confidence) on the error of estimated ranks as a fraction of the number of values, which is about 1.3% for k = 200
+    pub fn rank_error(&self) -> f64 {
+        2.296 / (self.k as f64).powf(0.9723)
+    }
+
+    /// Returns the estimated fraction of the values added to the sketch that are less than or equal to a value (0 if the sketch is empty)
+    pub fn rank(&self, val: f64) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let mut weight: u64 = 0;
+        for (height, level) in self.levels.iter().enumerate() {
+            weight += (level.iter().filter(|item| **item <= val).count() as u64) << height;
+        }
+        weight as f64 / self.num_vals as f64
+    }
+
+    /// Returns the estimated value at a given quantile (between 0 and 1) of the values added to the sketch, or a ClassifyError if the sketch is empty or the quantile is out of range
+    ///
+    /// The quantiles 0 and 1 return the exact minimum and maximum
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::QuantileSketch;
+    ///
+    /// let mut sketch = QuantileSketch::default();
+    /// sketch.try_extend(&(1..=10000).collect::<Vec<u32>>()).unwrap();
+    ///
+    /// let median = sketch.try_quantile(0.5).unwrap();
+    /// assert!((median - 5000.0).abs() <= 10000.0 * sketch.rank_error());
+    /// assert_eq!(sketch.try_quantile(1.0), Ok(10000.0));
+    /// ```
+    pub fn try_quantile(&self, quantile: f64) -> Result<f64, ClassifyError> {
+        if self.is_empty() {
+            return Err(ClassifyError::EmptyInput);
+        }
+        if !(0.0..=1.0).contains(&quantile) {
+            return Err(ClassifyError::InvalidParameter(
+                "quantile must be between 0 and 1".to_string(),
+            ));
+        }
+        let (vals, weights) = self.try_to_weighted()?;
+        let target = quantile * self.num_vals as f64;
+        let mut cum_weight: u64 = 0;
+        for (val, weight) in vals.iter().zip(weights.iter()) {
+            cum_weight += weight;
+            if cum_weight as f64 >= target {
+                return Ok(*val);
+            }
+        }
+        Ok(self.max.unwrap())
+    }
+
+    /// Returns the retained samples sorted in ascending order along with their weights (the number of values each stands for), with the lowest and highest samples moved to the exact minimum and maximum, or a ClassifyError if the sketch is empty
+    pub(crate) fn try_to_weighted(&self) -> Result<(Vec<f64>, Vec<u64>), ClassifyError> {
+        if self.is_empty() {
+            return Err(ClassifyError::EmptyInput);
+        }
+        let (mut vals, weights) = self.samples();
+        let last = vals.len() - 1;
+        vals[0] = self.min.unwrap();
+        vals[last] = self.max.unwrap();
+        Ok((vals, weights))
+    }
+
+    /// Returns the retained samples sorted in ascending order along with their weights
+    fn samples(&self) -> (Vec<f64>, Vec<u64>) {
+        let mut pairs: Vec<(f64, u64)> = vec![];
+        for (height, level) in self.levels.iter().enumerate() {
+            pairs.extend(level.iter().map(|item| (*item, 1_u64 << height)));
+        }
+        pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        pairs.into_iter().unzip()
+    }
+
+    /// Adds a finite value to the lowest level, compacting levels that exceed their capacity
+    fn push(&mut self, val: f64) {
+        self.levels[0].push(val);
+        self.num_vals += 1;
+        self.min = combine(self.min, Some(val), f64::min);
+        self.max = combine(self.max, Some(val), f64::max);
+        self.compress();
+    }
+
+    /// Returns the number of samples a level may hold before it is compacted, which shrinks geometrically from k at the top level
+    fn capacity(&self, height: usize) -> usize {
+        let depth = self.levels.len() - 1 - height;
+        std::cmp::max(
+            2,
+            (self.k as f64 * (2.0_f64 / 3.0).powi(depth as i32)).ceil() as usize,
+        )
+    }
+
+    /// Compacts the lowest full level until the sketch retains no more samples than its total capacity
+    fn compress(&mut self) {
+        loop {
+            let total_capacity: usize = (0..self.levels.len()).map(|h| self.capacity(h)).sum();
+            if self.num_retained() <= total_capacity {
+                return;
+            }
+            let height = (0..self.levels.len())
+                .find(|h| self.levels[*h].len() >= self.capacity(*h))
+                .unwrap();
+            self.compact(height);
+        }
+    }
+
+    /// Sorts a level and promotes every other sample (starting from a randomly chosen offset) to the level above, where each sample counts twice as much; the largest sample stays behind if the level holds an odd number of samples
+    fn compact(&mut self, height: usize) {
+        if height + 1 == self.levels.len() {
+            self.levels.push(vec![]);
+        }
+        let mut level = std::mem::take(&mut self.levels[height]);
+        level.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if level.len() % 2 == 1 {
+            self.levels[height].push(level.pop().unwrap());
+        }
+        let offset = self.flip_coin();
+        let promoted: Vec<f64> = level.into_iter().skip(offset).step_by(2).collect();
+        self.levels[height + 1].extend(promoted);
+    }
+
+    /// Returns 0 or 1 from a xorshift generator, so that compactions are unbiased yet the sketch is reproducible
+    fn flip_coin(&mut self) -> usize {
+        self.coin ^= self.coin << 13;
+        self.coin ^= self.coin >> 7;
+        self.coin ^= self.coin << 17;
+        (self.coin & 1) as usize
+    }
+}
+
+/// Returns the combination of two optional values, or whichever is set if only one of them is
+fn combine(a: Option<f64>, b: Option<f64>, f: fn(f64, f64) -> f64) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(f(a, b)),
+        _ => a.or(b),
+    }
+}
@@ -0,0 +1,224 @@
+use std::borrow::Cow;
+
+use num_traits::ToPrimitive;
+
+use crate::error::ClassifyError;
+use crate::missing::{apply_missing_policy, MissingPolicy};
+use crate::utilities::{create_unique_val_mapping, try_to_vec_f64, PrefixSums, UniqueVal};
+
+/// Represents a dataset sorted once along with the statistics shared by every classification algorithm, so that several methods can be run on the same data without sorting it again
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, HeadTail, Jenks, Quantile, SortedData};
+///
+/// let data: Vec<f64> = vec![8.0, 1.0, 5.0, 2.0, 7.0, 4.0];
+/// let sorted = SortedData::new(&data);
+///
+/// assert_eq!(sorted.values(), &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]);
+/// assert_eq!((sorted.min(), sorted.max(), sorted.mean()), (1.0, 8.0, 4.5));
+///
+/// assert_eq!(Jenks { num_bins: 3 }.breaks_sorted(&sorted), Jenks { num_bins: 3 }.breaks(&data));
+/// assert_eq!(HeadTail.breaks_sorted(&sorted), HeadTail.breaks(&data));
+/// assert_eq!(
+///     Quantile { num_bins: 2, definition: Default::default() }.breaks_sorted(&sorted),
+///     Ok(vec![4.5])
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortedData {
+    values: Vec<f64>,
+    unique_vals: Vec<UniqueVal>,
+    sums: Option<SortedSums>,
+    mean: f64,
+    variance: f64,
+}
+
+/// Represents the prefix sums of a SortedData object, which are only needed by the algorithms and metrics that calculate sums of squared deviations
+#[derive(Debug, Clone, PartialEq)]
+struct SortedSums {
+    prefix_sums: Vec<f64>,
+    prefix_sums_squares: Vec<f64>,
+    centered_sums: PrefixSums,
+}
+
+impl SortedSums {
+    fn new(values: &[f64]) -> Self {
+        let mut prefix_sums: Vec<f64> = vec![0.0; values.len() + 1];
+        let mut prefix_sums_squares: Vec<f64> = vec![0.0; values.len() + 1];
+        for (i, item) in values.iter().enumerate() {
+            prefix_sums[i + 1] = prefix_sums[i] + item;
+            prefix_sums_squares[i + 1] = prefix_sums_squares[i] + item * item;
+        }
+        SortedSums {
+            prefix_sums,
+            prefix_sums_squares,
+            centered_sums: PrefixSums::new(values),
+        }
+    }
+}
+
+impl SortedData {
+    /// Returns a SortedData object built from unsorted data
+    ///
+    /// # Edge Cases
+    ///
+    /// * Panics on empty data or non-finite data; use SortedData::try_new to handle these cases instead
+    pub fn new<T: ToPrimitive>(data: &[T]) -> Self {
+        SortedData::try_new(data).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Returns a SortedData object built from unsorted data, or a ClassifyError if the data is empty or if any value can't be converted or isn't finite
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{ClassifyError, SortedData};
+    ///
+    /// assert_eq!(SortedData::try_new(&[3, 1, 2]).unwrap().values(), &[1.0, 2.0, 3.0]);
+    /// assert_eq!(SortedData::try_new(&[1.0, f64::NAN]), Err(ClassifyError::NonFiniteValue(1)));
+    /// ```
+    pub fn try_new<T: ToPrimitive>(data: &[T]) -> Result<Self, ClassifyError> {
+        let mut sorted = SortedData::try_new_without_sums(data)?;
+        sorted.sums = Some(SortedSums::new(&sorted.values));
+        Ok(sorted)
+    }
+
+    /// Returns a SortedData object built from unsorted data without taking its prefix sums, which the accessors below then calculate on demand
+    ///
+    /// Used by the single-method functions, most of which never need the prefix sums, so that they don't pay for them on every call
+    pub(crate) fn try_new_without_sums<T: ToPrimitive>(data: &[T]) -> Result<Self, ClassifyError> {
+        let mut values = try_to_vec_f64(data)?;
+        let num_vals = values.len();
+
+        // The mean and variance are taken in the original order of the data so that they match the unsorted algorithms exactly
+        let mut mean = 0.0;
+        for item in &values {
+            mean += item;
+        }
+        mean /= num_vals as f64;
+        let mut variance = 0.0;
+        if num_vals > 1 {
+            for item in &values {
+                variance += (mean - item) * (mean - item);
+            }
+            variance /= (num_vals - 1) as f64;
+        }
+
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let mut unique_vals: Vec<UniqueVal> = vec![];
+        create_unique_val_mapping(&mut unique_vals, &values);
+
+        Ok(SortedData {
+            values,
+            unique_vals,
+            sums: None,
+            mean,
+            variance,
+        })
+    }
+
+    /// Returns a SortedData object built from the values of unsorted data that aren't missing according to a MissingPolicy, along with the number of values that were skipped, or a ClassifyError if the input is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{MissingPolicy, SortedData};
+    ///
+    /// let data: Vec<f64> = vec![3.0, -9999.0, 1.0, f64::NAN, 2.0];
+    ///
+    /// let (sorted, num_skipped) = SortedData::try_new_with_policy(&data, &MissingPolicy::Sentinels(vec![-9999.0])).unwrap();
+    ///
+    /// assert_eq!((sorted.values(), num_skipped), (&[1.0, 2.0, 3.0][..], 2));
+    /// ```
+    pub fn try_new_with_policy<T: ToPrimitive>(
+        data: &[T],
+        policy: &MissingPolicy,
+    ) -> Result<(Self, usize), ClassifyError> {
+        let (values, num_skipped) = apply_missing_policy(data, policy)?;
+        Ok((SortedData::try_new(&values)?, num_skipped))
+    }
+
+    /// Returns the data points sorted in ascending order
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the unique values of the data along with the indices of their first and last occurrences among the sorted data points
+    pub fn unique_vals(&self) -> &[UniqueVal] {
+        &self.unique_vals
+    }
+
+    /// Returns the prefix sums of the sorted data points, where the element at index i is the sum of the first i points
+    pub fn prefix_sums(&self) -> Cow<'_, [f64]> {
+        match &self.sums {
+            Some(sums) => Cow::Borrowed(&sums.prefix_sums),
+            None => Cow::Owned(SortedSums::new(&self.values).prefix_sums),
+        }
+    }
+
+    /// Returns the prefix sums of the squares of the sorted data points, where the element at index i is the sum of the squares of the first i points
+    pub fn prefix_sums_squares(&self) -> Cow<'_, [f64]> {
+        match &self.sums {
+            Some(sums) => Cow::Borrowed(&sums.prefix_sums_squares),
+            None => Cow::Owned(SortedSums::new(&self.values).prefix_sums_squares),
+        }
+    }
+
+    /// Returns the sum of squared deviations of the sorted data points first..end around their mean in constant time (0 for an empty range)
+    ///
+    /// The deviations are calculated from prefix sums taken around the median of the data, which limits the loss of precision for data far from zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::SortedData;
+    ///
+    /// let sorted = SortedData::new(&[7.0, 1.0, 5.0, 2.0, 8.0, 4.0]);
+    ///
+    /// assert_eq!(sorted.ssd(0, 2), 0.5);
+    /// assert_eq!(sorted.ssd(2, 6), 10.0);
+    /// ```
+    pub fn ssd(&self, first: usize, end: usize) -> f64 {
+        match &self.sums {
+            Some(sums) => sums.centered_sums.ssd(first, end),
+            None if first < end => PrefixSums::new(&self.values[first..end]).ssd(0, end - first),
+            None => 0.0,
+        }
+    }
+
+    /// Returns the prefix sums of the sorted data points taken around the median, used to calculate sums of squared deviations in constant time
+    pub(crate) fn centered_sums(&self) -> Cow<'_, PrefixSums> {
+        match &self.sums {
+            Some(sums) => Cow::Borrowed(&sums.centered_sums),
+            None => Cow::Owned(PrefixSums::new(&self.values)),
+        }
+    }
+
+    /// Returns the number of data points
+    pub fn num_vals(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the smallest data point
+    pub fn min(&self) -> f64 {
+        self.values[0]
+    }
+
+    /// Returns the largest data point
+    pub fn max(&self) -> f64 {
+        self.values[self.values.len() - 1]
+    }
+
+    /// Returns the mean of the data points
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the variance of the data points using Bessel's correction (0 for a single data point)
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+}
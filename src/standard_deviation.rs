@@ -1,5 +1,10 @@
+use crate::classifier::{Classifier, StdDev};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
 use crate::utilities::Classification;
-use crate::utilities::{breaks_to_classification, to_vec_f64};
+use crate::utilities::{try_param_to_f64, try_split_histogram};
+use crate::utilities::{try_to_weighted_vals, try_to_weights, WeightedVal};
 use num_traits::ToPrimitive;
 
 /// Returns a Classification object following the Standard Deviation Breaks algorithm given the desired bin size as a proportion of a standard deviation and one-dimensional data
@@ -13,6 +18,7 @@ use num_traits::ToPrimitive;
 /// # Edge cases
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * Panics on empty data, non-finite data or a bin size that isn't positive; use try_get_st_dev_classification to handle these cases instead
 ///
 /// # Examples
 ///
@@ -24,11 +30,11 @@ use num_traits::ToPrimitive;
 /// let bin_size = 1.0; // Bins will be the size of one standard deviation
 ///
 /// let result: Classification = get_st_dev_classification(bin_size, &data);
-/// let expected: Classification = vec![
-///     Bin{bin_start: 0.0, bin_end: 0.41987655026535653, count: 1},
-///     Bin{bin_start: 0.41987655026535653, bin_end: 1.5, count: 2},
-///     Bin{bin_start: 1.5, bin_end: 2.5801234497346437, count: 3},
-///     Bin{bin_start: 2.5801234497346437, bin_end: 3.0, count: 1}
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(0.0, 0.41987655026535653, 1),
+///     Bin::new(0.41987655026535653, 1.5, 2),
+///     Bin::new(1.5, 2.5801234497346437, 3),
+///     Bin::new(2.5801234497346437, 3.0, 1)
 /// ];
 ///
 /// assert!(result == expected);
@@ -37,8 +43,52 @@ pub fn get_st_dev_classification<T: ToPrimitive, S: ToPrimitive>(
     bin_size: S,
     data: &[T],
 ) -> Classification {
-    let breaks: Vec<f64> = get_st_dev_breaks(bin_size, data);
-    breaks_to_classification(&breaks, data)
+    try_get_st_dev_classification(bin_size, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following the Standard Deviation Breaks algorithm given the desired bin size as a proportion of a standard deviation and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `bin_size` - A float representing the proportion of a standard deviation each bin should encompass
+/// * `data` - A reference to a vector of unsorted data points (f64) to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_st_dev_classification;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f32> = vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0];
+///
+/// assert!(try_get_st_dev_classification(-1.0, &data).is_err());
+/// ```
+pub fn try_get_st_dev_classification<T: ToPrimitive, S: ToPrimitive>(
+    bin_size: S,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    StdDev {
+        bin_size: try_param_to_f64("bin_size", bin_size)?,
+    }
+    .classify(data)
+}
+
+/// Returns a Classification object following the Standard Deviation Breaks algorithm given the desired bin size as a proportion of a standard deviation and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `bin_size` - A float representing the proportion of a standard deviation each bin should encompass
+/// * `data` - A reference to a vector of unsorted data points (f64) to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_st_dev_classification_with_policy<T: ToPrimitive, S: ToPrimitive>(
+    bin_size: S,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    StdDev {
+        bin_size: try_param_to_f64("bin_size", bin_size)?,
+    }
+    .classify_with_policy(data, policy)
 }
 
 /// Returns a vector of breaks generated through the Standard Deviation Breaks algorithm given the desired bin size as a proportion of a standard deviation and a dataset
@@ -52,6 +102,7 @@ pub fn get_st_dev_classification<T: ToPrimitive, S: ToPrimitive>(
 /// # Edge cases
 ///
 /// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * Panics on empty data, non-finite data or a bin size that isn't positive; use try_get_st_dev_breaks to handle these cases instead
 ///
 /// # Examples
 ///
@@ -66,25 +117,169 @@ pub fn get_st_dev_classification<T: ToPrimitive, S: ToPrimitive>(
 /// assert_eq!(result, vec![0.41987655026535653, 1.5, 2.5801234497346437]);
 /// ```
 pub fn get_st_dev_breaks<T: ToPrimitive, S: ToPrimitive>(bin_size: S, data: &[T]) -> Vec<f64> {
-    let bin_size = bin_size.to_f64().unwrap();
-    let data = to_vec_f64(data);
+    try_get_st_dev_breaks(bin_size, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks generated through the Standard Deviation Breaks algorithm given the desired bin size as a proportion of a standard deviation and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `bin_size` - A float representing the proportion of a standard deviation each bin should encompass
+/// * `data` - A reference to a collection of unsorted data points (f64) to generate breaks for
+///
+/// # Edge cases
+///
+/// * If the data has no spread (a single value, or all values equal), the only break returned is the mean
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_st_dev_breaks;
+///
+/// let data: Vec<f32> = vec![4.0];
+///
+/// assert_eq!(try_get_st_dev_breaks(1.0, &data), Ok(vec![4.0]));
+/// ```
+pub fn try_get_st_dev_breaks<T: ToPrimitive, S: ToPrimitive>(
+    bin_size: S,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    let bin_size = try_bin_size(bin_size)?;
+
+    try_get_st_dev_breaks_sorted(bin_size, &SortedData::try_new_without_sums(data)?)
+}
+
+/// Returns a vector of breaks generated through the Standard Deviation Breaks algorithm given the desired bin size as a proportion of a standard deviation and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `bin_size` - A float representing the proportion of a standard deviation each bin should encompass
+/// * `data` - A reference to a collection of unsorted data points (f64) to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_st_dev_breaks, try_get_st_dev_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_st_dev_breaks_with_policy(1.0, &data, &policy), try_get_st_dev_breaks(1.0, &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_st_dev_breaks_with_policy(1.0, &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_st_dev_breaks_with_policy<T: ToPrimitive, S: ToPrimitive>(
+    bin_size: S,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    StdDev {
+        bin_size: try_param_to_f64("bin_size", bin_size)?,
+    }
+    .breaks_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks generated through the Standard Deviation Breaks algorithm given the desired bin size and a SortedData object, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_st_dev_breaks_sorted(
+    bin_size: f64,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    let bin_size = try_bin_size(bin_size)?;
+
+    let mean = sorted.mean();
+    if sorted.num_vals() < 2 || sorted.min() == sorted.max() {
+        return Ok(vec![mean]);
+    }
 
-    let mut min_value = data[0];
-    let mut max_value = data[0];
+    Ok(calc_st_dev_breaks(
+        mean,
+        sorted.variance().sqrt() * bin_size,
+        sorted.min(),
+        sorted.max(),
+    ))
+}
+
+/// Returns a vector of breaks generated through the Standard Deviation Breaks algorithm given the desired bin size and a weighted dataset, using the weighted mean and the weighted standard deviation, or a ClassifyError if the input is invalid
+///
+/// The standard deviation uses the unbiased estimator for reliability weights, dividing by V1 - V2 / V1 (where V1 and V2 are the sums of the weights and of their squares), so that scaling every weight by the same constant doesn't change the breaks and uniform weights give the same breaks as unweighted data
+pub(crate) fn try_get_st_dev_breaks_weighted<T: ToPrimitive, S: ToPrimitive, W: ToPrimitive>(
+    bin_size: S,
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<f64>, ClassifyError> {
+    let bin_size = try_bin_size(bin_size)?;
+    let weighted_vals = try_to_weighted_vals(data, weights)?;
+    let sum_squared_weights: f64 = try_to_weights(weights, data.len())?
+        .iter()
+        .map(|weight| weight * weight)
+        .sum();
+
+    Ok(calc_weighted_st_dev_breaks(
+        bin_size,
+        &weighted_vals,
+        |total_weight| total_weight - sum_squared_weights / total_weight,
+    ))
+}
+
+/// Returns a vector of breaks generated through the Standard Deviation Breaks algorithm given the desired bin size and a histogram of (value, count) pairs, using Bessel's correction on the total count so that the breaks match those of the expanded data, or a ClassifyError if the input is invalid
+pub(crate) fn try_get_st_dev_breaks_from_histogram<S: ToPrimitive>(
+    bin_size: S,
+    histogram: &[(f64, u64)],
+) -> Result<Vec<f64>, ClassifyError> {
+    let bin_size = try_bin_size(bin_size)?;
+    let (vals, counts) = try_split_histogram(histogram)?;
+    let weighted_vals = try_to_weighted_vals(&vals, &counts)?;
+
+    Ok(calc_weighted_st_dev_breaks(
+        bin_size,
+        &weighted_vals,
+        |total_count| total_count - 1.0,
+    ))
+}
+
+/// Returns the Standard Deviation breaks of non-empty sorted weighted unique values given the bin size and a function returning the denominator of the variance from the total weight, or only the weighted mean if the denominator isn't positive or the values are all equal
+fn calc_weighted_st_dev_breaks(
+    bin_size: f64,
+    weighted_vals: &[WeightedVal],
+    denominator: impl Fn(f64) -> f64,
+) -> Vec<f64> {
+    let mut total_weight = 0.0;
     let mut mean = 0.0;
-    for item in &data {
-        mean += item;
-        if *item < min_value {
-            min_value = *item;
-        } else if *item > max_value {
-            max_value = *item
-        }
+    for item in weighted_vals {
+        total_weight += item.weight;
+        mean += item.val * item.weight;
+    }
+    mean /= total_weight;
+
+    let min_value = weighted_vals[0].val;
+    let max_value = weighted_vals[weighted_vals.len() - 1].val;
+    let denominator = denominator(total_weight);
+    if denominator <= 0.0 || min_value == max_value {
+        return vec![mean];
     }
-    mean /= data.len() as f64;
 
-    let st_dev = calc_st_dev(&data);
-    let new_dev = st_dev * bin_size;
+    let mut sum_squared_dev = 0.0;
+    for item in weighted_vals {
+        sum_squared_dev += (mean - item.val) * (mean - item.val) * item.weight;
+    }
+    let st_dev = (sum_squared_dev / denominator).sqrt();
 
+    calc_st_dev_breaks(mean, st_dev * bin_size, min_value, max_value)
+}
+
+/// Translates the bin size to f64, returning an error if it can't be converted, isn't finite or isn't positive
+fn try_bin_size<S: ToPrimitive>(bin_size: S) -> Result<f64, ClassifyError> {
+    let bin_size = try_param_to_f64("bin_size", bin_size)?;
+    if bin_size <= 0.0 {
+        return Err(ClassifyError::InvalidParameter(
+            "bin_size must be greater than zero".to_string(),
+        ));
+    }
+    Ok(bin_size)
+}
+
+/// Returns the breaks one bin width apart, starting from the mean, that cover the range of the data
+fn calc_st_dev_breaks(mean: f64, new_dev: f64, min_value: f64, max_value: f64) -> Vec<f64> {
     let devs_below_mean = ((mean - min_value) / new_dev) as isize;
     let devs_above_mean = ((max_value - mean) / new_dev) as isize;
 
@@ -92,7 +287,6 @@ pub fn get_st_dev_breaks<T: ToPrimitive, S: ToPrimitive>(bin_size: S, data: &[T]
     for i in -devs_below_mean..(devs_above_mean + 1) {
         breaks.push(mean + (i as f64) * new_dev);
     }
-
     breaks
 }
 
@@ -101,7 +295,7 @@ pub fn get_st_dev_breaks<T: ToPrimitive, S: ToPrimitive>(bin_size: S, data: &[T]
 /// # Arguments
 ///
 /// * `data` - A reference to a collection containing data to calculate standard deviation for
-pub fn calc_st_dev(data: &Vec<f64>) -> f64 {
+pub fn calc_st_dev(data: &[f64]) -> f64 {
     let mut mean: f64 = 0.0;
     for val in data {
         mean += *val
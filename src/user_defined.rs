@@ -0,0 +1,325 @@
+use num_traits::ToPrimitive;
+use std::str::FromStr;
+
+use crate::classifier::{Classifier, UserDefined};
+use crate::error::ClassifyError;
+use crate::missing::MissingPolicy;
+use crate::sorted_data::SortedData;
+use crate::utilities::try_to_vec_f64;
+use crate::utilities::Classification;
+
+impl UserDefined {
+    /// Creates a validated UserDefined configuration given the edges of the classes and whether to add open-ended classes below the first edge and above the last edge, sorting the edges and removing duplicates
+    ///
+    /// # Arguments
+    ///
+    /// * `edges` - A reference to a collection of class edges (e.g. [0, 10, 25] for the classes 0-10 and 10-25)
+    /// * `open_lower` - Whether to add an open-ended class for values below the first edge
+    /// * `open_upper` - Whether to add an open-ended class for values above the last edge
+    ///
+    /// # Edge Cases
+    ///
+    /// * Returns ClassifyError::InvalidParameter if any edge isn't finite or if the edges don't define at least one class
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::UserDefined;
+    ///
+    /// let method = UserDefined::new(&[50.0, 0.0, 10.0, 10.0], false, true).unwrap();
+    ///
+    /// assert_eq!(method.edges, vec![0.0, 10.0, 50.0]);
+    /// assert!(UserDefined::new(&[5.0], false, false).is_err());
+    /// ```
+    pub fn new(edges: &[f64], open_lower: bool, open_upper: bool) -> Result<Self, ClassifyError> {
+        if edges.iter().any(|item| !item.is_finite()) {
+            return Err(ClassifyError::InvalidParameter(
+                "class edges must be finite numbers".to_string(),
+            ));
+        }
+        let mut edges: Vec<f64> = edges.to_vec();
+        edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        edges.dedup();
+
+        let num_classes =
+            edges.len().saturating_sub(1) + usize::from(open_lower) + usize::from(open_upper);
+        if edges.is_empty() || num_classes == 0 {
+            return Err(ClassifyError::InvalidParameter(
+                "class edges must define at least one class".to_string(),
+            ));
+        }
+
+        Ok(UserDefined {
+            edges,
+            open_lower,
+            open_upper,
+        })
+    }
+}
+
+impl FromStr for UserDefined {
+    type Err = ClassifyError;
+
+    /// Parses a comma-separated list of class edges, where the first edge may be prefixed with "<" and the last edge with ">" to add open-ended classes (e.g. "<0, 10, 25, 50, >100")
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = spec.split(',').map(|token| token.trim()).collect();
+        let num_tokens = tokens.len();
+
+        let mut edges: Vec<f64> = vec![];
+        let mut open_lower = false;
+        let mut open_upper = false;
+        for (i, token) in tokens.iter().enumerate() {
+            let mut value = *token;
+            if i == 0 && value.starts_with('<') {
+                open_lower = true;
+                value = value[1..].trim_start();
+            }
+            if i == num_tokens - 1 && value.starts_with('>') {
+                open_upper = true;
+                value = value[1..].trim_start();
+            }
+            let edge = value.parse::<f64>().map_err(|_| {
+                ClassifyError::InvalidParameter(format!("invalid class edge '{}'", token))
+            })?;
+            edges.push(edge);
+        }
+
+        UserDefined::new(&edges, open_lower, open_upper)
+    }
+}
+
+/// Returns a Classification object following User-Defined Breaks given a spec string of class edges and one-dimensional data
+///
+/// # Arguments
+///
+/// * `spec` - A comma-separated list of class edges, where the first edge may be prefixed with "<" and the last edge with ">" to add open-ended classes (e.g. "<0, 10, 25, 50, >100")
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * Classes lying outside the range of the data are dropped
+/// * Panics on an invalid spec, empty data, non-finite data or data outside of the classes; use try_get_user_defined_classification to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_user_defined_classification;
+/// use classify::{Classification, Bin};
+///
+/// let data: Vec<f64> = vec![-3.0, 5.0, 12.0, 30.0, 60.0, 140.0];
+///
+/// let result: Classification = get_user_defined_classification("<0, 10, 25, 50, >100", &data);
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(-3.0, 0.0, 1),
+///     Bin::new(0.0, 10.0, 1),
+///     Bin::new(10.0, 25.0, 1),
+///     Bin::new(25.0, 50.0, 1),
+///     Bin::new(50.0, 100.0, 1),
+///     Bin::new(100.0, 140.0, 1)
+/// ];
+///
+/// assert!(result == expected);
+/// ```
+pub fn get_user_defined_classification<T: ToPrimitive>(spec: &str, data: &[T]) -> Classification {
+    try_get_user_defined_classification(spec, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object following User-Defined Breaks given a spec string of class edges and one-dimensional data, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `spec` - A comma-separated list of class edges, where the first edge may be prefixed with "<" and the last edge with ">" to add open-ended classes (e.g. "<0, 10, 25, 50, >100")
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_user_defined_classification;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 3.0];
+///
+/// assert!(try_get_user_defined_classification("0, ten, 20", &data).is_err());
+/// ```
+pub fn try_get_user_defined_classification<T: ToPrimitive>(
+    spec: &str,
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    spec.parse::<UserDefined>()?.classify(data)
+}
+
+/// Returns a Classification object following User-Defined Breaks given a spec string of class edges and one-dimensional data, handling missing values according to a MissingPolicy and reporting how many were skipped, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `spec` - A comma-separated list of class edges, where the first edge may be prefixed with "<" and the last edge with ">" to add open-ended classes (e.g. "<0, 10, 25, 50, >100")
+/// * `data` - A reference to a collection of unsorted data points to generate a Classification for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+pub fn try_get_user_defined_classification_with_policy<T: ToPrimitive>(
+    spec: &str,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    spec.parse::<UserDefined>()?
+        .classify_with_policy(data, policy)
+}
+
+/// Returns a vector of breaks following User-Defined Breaks given a spec string of class edges and a dataset
+///
+/// # Arguments
+///
+/// * `spec` - A comma-separated list of class edges, where the first edge may be prefixed with "<" and the last edge with ">" to add open-ended classes (e.g. "<0, 10, 25, 50, >100")
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Edge Cases
+///
+/// * Inputting large u64/i64 data (near their max values) will result in loss of precision because data is being cast to f64
+/// * Breaks at or outside the minimum and maximum of the data are dropped, since the classes they bound would be empty
+/// * Panics on an invalid spec, empty data, non-finite data or data outside of the classes; use try_get_user_defined_breaks to handle these cases instead
+///
+/// # Examples
+///
+/// ```
+/// use classify::get_user_defined_breaks;
+///
+/// let data: Vec<i32> = vec![3, 8, 15, 40, 80];
+///
+/// let result: Vec<f64> = get_user_defined_breaks("0, 10, 25, 50, 100", &data);
+///
+/// assert_eq!(result, vec![10.0, 25.0, 50.0]);
+/// ```
+pub fn get_user_defined_breaks<T: ToPrimitive>(spec: &str, data: &[T]) -> Vec<f64> {
+    try_get_user_defined_breaks(spec, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a vector of breaks following User-Defined Breaks given a spec string of class edges and a dataset, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `spec` - A comma-separated list of class edges, where the first edge may be prefixed with "<" and the last edge with ">" to add open-ended classes (e.g. "<0, 10, 25, 50, >100")
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_get_user_defined_breaks;
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![3.0, 8.0, 15.0, 140.0];
+///
+/// assert_eq!(try_get_user_defined_breaks("0, 10, 25, 50, >100", &data), Ok(vec![10.0, 25.0, 50.0, 100.0]));
+/// assert_eq!(
+///     try_get_user_defined_breaks("0, 10, 25, 50, 100", &data),
+///     Err(ClassifyError::InvalidParameter("data point at index 3 is outside of the user-defined classes".to_string()))
+/// );
+/// ```
+pub fn try_get_user_defined_breaks<T: ToPrimitive>(
+    spec: &str,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    spec.parse::<UserDefined>()?.breaks(data)
+}
+
+/// Returns a vector of breaks following User-Defined Breaks given a spec string of class edges and a dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `spec` - A comma-separated list of class edges, where the first edge may be prefixed with "<" and the last edge with ">" to add open-ended classes (e.g. "<0, 10, 25, 50, >100")
+/// * `data` - A reference to a collection of unsorted data points to generate breaks for
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are handled
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_get_user_defined_breaks, try_get_user_defined_breaks_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 4.0, 5.0, -9999.0, 7.0, 8.0];
+/// let policy = MissingPolicy::Sentinels(vec![-9999.0]);
+///
+/// assert_eq!(try_get_user_defined_breaks_with_policy("0, 3, 6, 9", &data, &policy), try_get_user_defined_breaks("0, 3, 6, 9", &[1.0, 2.0, 4.0, 5.0, 7.0, 8.0]));
+/// assert!(try_get_user_defined_breaks_with_policy("0, 3, 6, 9", &data, &MissingPolicy::Error).is_err());
+/// ```
+pub fn try_get_user_defined_breaks_with_policy<T: ToPrimitive>(
+    spec: &str,
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Vec<f64>, ClassifyError> {
+    spec.parse::<UserDefined>()?
+        .breaks_with_policy(data, policy)
+}
+
+/// Returns the breaks of a UserDefined configuration on a dataset, or a ClassifyError if the configuration is invalid or any data point falls outside of its classes
+pub(crate) fn calc_user_defined_breaks<T: ToPrimitive>(
+    method: &UserDefined,
+    data: &[T],
+) -> Result<Vec<f64>, ClassifyError> {
+    let method = UserDefined::new(&method.edges, method.open_lower, method.open_upper)?;
+    let data = try_to_vec_f64(data)?;
+
+    let num_edges = method.edges.len();
+    let lowest_edge = method.edges[0];
+    let highest_edge = method.edges[num_edges - 1];
+    let mut min_value = data[0];
+    let mut max_value = data[0];
+    for (i, item) in data.iter().enumerate() {
+        if (!method.open_lower && *item < lowest_edge)
+            || (!method.open_upper && *item > highest_edge)
+        {
+            return Err(ClassifyError::InvalidParameter(format!(
+                "data point at index {} is outside of the user-defined classes",
+                i
+            )));
+        }
+        if *item < min_value {
+            min_value = *item;
+        } else if *item > max_value {
+            max_value = *item;
+        }
+    }
+
+    Ok(select_user_defined_breaks(&method, min_value, max_value))
+}
+
+/// Returns the breaks of a UserDefined configuration on a SortedData object, or a ClassifyError if the configuration is invalid or any data point lies outside of a closed class
+pub(crate) fn calc_user_defined_breaks_sorted(
+    method: &UserDefined,
+    sorted: &SortedData,
+) -> Result<Vec<f64>, ClassifyError> {
+    let method = UserDefined::new(&method.edges, method.open_lower, method.open_upper)?;
+
+    let lowest_edge = method.edges[0];
+    let highest_edge = method.edges[method.edges.len() - 1];
+    for item in [sorted.min(), sorted.max()] {
+        if (!method.open_lower && item < lowest_edge) || (!method.open_upper && item > highest_edge)
+        {
+            return Err(ClassifyError::InvalidParameter(format!(
+                "data point {} is outside of the user-defined classes",
+                item
+            )));
+        }
+    }
+
+    Ok(select_user_defined_breaks(
+        &method,
+        sorted.min(),
+        sorted.max(),
+    ))
+}
+
+/// Returns the edges of a validated UserDefined configuration lying strictly within the range of the data, leaving out the outer edges of closed classes
+fn select_user_defined_breaks(method: &UserDefined, min_value: f64, max_value: f64) -> Vec<f64> {
+    let num_edges = method.edges.len();
+    let first = if method.open_lower { 0 } else { 1 };
+    let last = if method.open_upper {
+        num_edges
+    } else {
+        num_edges - 1
+    };
+    let mut breaks: Vec<f64> = vec![];
+    for item in method.edges.iter().take(last).skip(first) {
+        if min_value < *item && *item < max_value {
+            breaks.push(*item);
+        }
+    }
+    breaks
+}
@@ -1,36 +1,345 @@
 use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+
+use crate::classifier::ClassificationMethod;
+use crate::error::ClassifyError;
+use crate::missing::{apply_missing_policy, MissingPolicy};
+use crate::parallel::map_slice;
 
 /// Represents a unique value found within a sorted dataset along with the indices of its first and last occurrences in the dataset
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct UniqueVal {
     pub val: f64,
     pub first: usize,
     pub last: usize,
 }
 
-/// Represents a single bin in a classification, including the bin's lowest (inclusive) and highest (exclusive) values and the number of points within it
+/// Represents a unique value found within a dataset along with the total weight of its occurrences (its number of occurrences if the data is unweighted)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedVal {
+    pub val: f64,
+    pub weight: f64,
+}
+
+/// Represents which end of the bins of a classification is closed (included in the bin)
+///
+/// Whatever the closure, the outer ends of a classification are always closed so that both the minimum and the maximum of the data fall within a bin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Closure {
+    /// Bins include their start and exclude their end, [a, b), except the last bin, which also includes its end, [a, b] (the default)
+    LeftClosed,
+    /// Bins exclude their start and include their end, (a, b], except the first bin, which also includes its start, [a, b] (as in PySAL)
+    RightClosed,
+}
+
+impl Default for Closure {
+    fn default() -> Self {
+        Closure::LeftClosed
+    }
+}
+
+/// Represents a single bin in a classification, including the bin's lowest and highest values, the number of points within it, their total weight and which of its ends is closed
+#[derive(Debug, Clone)]
 pub struct Bin {
     pub bin_start: f64,
     pub bin_end: f64,
     pub count: u64,
+    /// The total weight of the points within the bin (equal to the count for unweighted data)
+    pub weight_sum: f64,
+    pub closure: Closure,
+}
+
+impl Bin {
+    /// Creates a left-closed Bin of unweighted points given its lowest value, highest value and number of points
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{Bin, Closure};
+    ///
+    /// let bin = Bin::new(1.0, 4.0, 2);
+    ///
+    /// assert_eq!((bin.bin_start, bin.bin_end, bin.count), (1.0, 4.0, 2));
+    /// assert_eq!(bin.weight_sum, 2.0);
+    /// assert_eq!(bin.closure, Closure::LeftClosed);
+    /// ```
+    pub fn new(bin_start: f64, bin_end: f64, count: u64) -> Self {
+        Bin {
+            bin_start,
+            bin_end,
+            count,
+            weight_sum: count as f64,
+            closure: Closure::LeftClosed,
+        }
+    }
 }
 
 impl PartialEq for Bin {
     fn eq(&self, other: &Self) -> bool {
-        let starts_eq: bool = self.bin_start == other.bin_start;
-        let ends_eq: bool = self.bin_end == other.bin_end;
+        // The bounds of no-data bins are NaN, and two no-data bins are equal
+        let same = |a: f64, b: f64| a == b || (a.is_nan() && b.is_nan());
+        let starts_eq: bool = same(self.bin_start, other.bin_start);
+        let ends_eq: bool = same(self.bin_end, other.bin_end);
         let counts_eq: bool = self.count == other.count;
-        starts_eq && ends_eq && counts_eq
+        let weights_eq: bool = self.weight_sum == other.weight_sum;
+        let closures_eq: bool = self.closure == other.closure;
+        starts_eq && ends_eq && counts_eq && weights_eq && closures_eq
     }
 }
 
-/// Represents a full classification, which is a collection of Bin objects
-pub type Classification = Vec<Bin>;
+/// Represents goodness-of-fit statistics for a Classification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoodnessOfFit {
+    /// Goodness of variance fit (GVF), between 0 and 1 (higher is better)
+    pub gvf: f64,
+    /// Sum of squared deviations from the array (dataset) mean (SDAM)
+    pub sdam: f64,
+    /// Sum of squared deviations from the class means (SDCM)
+    pub sdcm: f64,
+}
+
+/// Represents a full classification, which is a collection of Bin objects along with metadata describing how it was generated
+///
+/// A Classification dereferences to a slice of its bins, so it can be indexed and iterated over like a vector of bins
+///
+/// # Examples
+///
+/// ```
+/// use classify::{Classifier, Jenks};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+///
+/// let class = Jenks { num_bins: 3 }.classify(&data).unwrap();
+///
+/// assert_eq!(class.len(), 3);
+/// assert_eq!(class[1].bin_start, 4.0);
+/// assert_eq!(class.method, Some(Jenks { num_bins: 3 }.into()));
+/// assert_eq!((class.num_vals, class.min, class.max), (6, 1.0, 8.0));
+/// assert_eq!(class.fit.unwrap().gvf, 0.96);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Classification {
+    /// The bins making up the classification, in ascending order, followed by the no-data bin (whose bounds are NaN) if there is one
+    pub bins: Vec<Bin>,
+    /// The algorithm and parameters used to generate the breaks (None if the breaks were supplied manually)
+    pub method: Option<ClassificationMethod>,
+    /// The number of data points that were classified
+    pub num_vals: usize,
+    /// The minimum of the classified data points
+    pub min: f64,
+    /// The maximum of the classified data points
+    pub max: f64,
+    /// Which end of the bins is closed, deciding the bin of values equal to a break
+    pub closure: Closure,
+    /// The number of NaN or missing data points that were skipped
+    pub num_skipped: usize,
+    /// The number of data points in the dedicated no-data bin, which is the last of the bins (None unless MissingPolicy::NoDataBin was used)
+    pub no_data_count: Option<u64>,
+    /// The MissingPolicy the data points were classified under, which classify_val and classify_values also apply (MissingPolicy::Error unless another policy was used)
+    pub missing_policy: MissingPolicy,
+    /// Goodness-of-fit statistics of the bins for the classified data (None if the data isn't available)
+    pub fit: Option<GoodnessOfFit>,
+}
+
+impl Classification {
+    /// Returns the breaks between the bins of this Classification (the start of every bin but the first)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::get_equal_interval_classification;
+    ///
+    /// let data: Vec<f64> = vec![0.0, 0.5, 1.0, 1.5, 2.5, 3.0];
+    ///
+    /// assert_eq!(get_equal_interval_classification(3, &data).breaks(), vec![1.0, 2.0]);
+    /// ```
+    pub fn breaks(&self) -> Vec<f64> {
+        self.value_bins()
+            .iter()
+            .skip(1)
+            .map(|bin| bin.bin_start)
+            .collect()
+    }
+
+    /// Returns the bins of this Classification that values fall into by range, leaving out the no-data bin if there is one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{try_breaks_to_classification_with_policy, MissingPolicy};
+    ///
+    /// let data: Vec<f64> = vec![1.0, 2.0, -9999.0, 5.0];
+    /// let policy = MissingPolicy::NoDataBin(vec![-9999.0]);
+    ///
+    /// let result = try_breaks_to_classification_with_policy(&[2.0], &data, &policy).unwrap();
+    ///
+    /// assert_eq!((result.len(), result.value_bins().len()), (3, 2));
+    /// ```
+    pub fn value_bins(&self) -> &[Bin] {
+        match self.no_data_count {
+            Some(_) => &self.bins[..self.bins.len().saturating_sub(1)],
+            None => &self.bins,
+        }
+    }
+
+    /// Returns the index of the dedicated no-data bin, which is the last bin, or None if this Classification has no no-data bin
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{classify_values, try_breaks_to_classification_with_policy, MissingPolicy};
+    ///
+    /// let data: Vec<f64> = vec![1.0, 2.0, -9999.0, 5.0];
+    /// let policy = MissingPolicy::NoDataBin(vec![-9999.0]);
+    ///
+    /// let result = try_breaks_to_classification_with_policy(&[2.0], &data, &policy).unwrap();
+    ///
+    /// assert_eq!(result.no_data_index(), Some(2));
+    /// assert_eq!(result[2].count, 1);
+    /// assert_eq!(result.labels(), vec!["[1, 2)", "[2, 5]", "No data"]);
+    /// assert_eq!(classify_values(&data, &result), vec![Some(0), Some(1), Some(2), Some(1)]);
+    /// ```
+    pub fn no_data_index(&self) -> Option<usize> {
+        self.no_data_count.map(|_| self.bins.len() - 1)
+    }
+
+    /// Returns a label for every bin of this Classification: an interval using brackets for closed ends and parentheses for open ends, or "No data" for the no-data bin
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use classify::{try_breaks_to_classification_with_closure, Closure};
+    ///
+    /// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+    ///
+    /// let left = try_breaks_to_classification_with_closure(&[4.0], &data, Closure::LeftClosed).unwrap();
+    /// let right = try_breaks_to_classification_with_closure(&[4.0], &data, Closure::RightClosed).unwrap();
+    ///
+    /// assert_eq!(left.labels(), vec!["[1, 4)", "[4, 8]"]);
+    /// assert_eq!(right.labels(), vec!["[1, 4]", "(4, 8]"]);
+    /// ```
+    pub fn labels(&self) -> Vec<String> {
+        let value_bins = self.value_bins();
+        let last_idx = value_bins.len().saturating_sub(1);
+        let mut labels: Vec<String> = vec![];
+        for (i, bin) in value_bins.iter().enumerate() {
+            let (open, close) = match self.closure {
+                Closure::LeftClosed if i == last_idx => ("[", "]"),
+                Closure::LeftClosed => ("[", ")"),
+                Closure::RightClosed if i == 0 => ("[", "]"),
+                Closure::RightClosed => ("(", "]"),
+            };
+            labels.push(format!(
+                "{}{}, {}{}",
+                open, bin.bin_start, bin.bin_end, close
+            ));
+        }
+        if self.no_data_count.is_some() {
+            labels.push("No data".to_string());
+        }
+        labels
+    }
+
+    /// Records the number and total weight of the data points skipped under a MissingPolicy, adding a no-data bin holding them if the policy asks for one
+    pub(crate) fn set_missing(
+        &mut self,
+        num_skipped: usize,
+        skipped_weight: f64,
+        policy: &MissingPolicy,
+    ) {
+        self.num_skipped = num_skipped;
+        if let MissingPolicy::NoDataBin(_) = policy {
+            self.no_data_count = Some(num_skipped as u64);
+            self.bins.push(Bin {
+                bin_start: f64::NAN,
+                bin_end: f64::NAN,
+                count: num_skipped as u64,
+                weight_sum: skipped_weight,
+                closure: self.closure,
+            });
+        }
+        self.missing_policy = policy.clone();
+    }
+
+    /// Returns the classification of a value (None if it couldn't be converted to f64) that is missing under this Classification's MissingPolicy, which is the index of the no-data bin if there is one, or None if the value isn't missing
+    ///
+    /// Under MissingPolicy::Error no value is treated as missing
+    fn classify_missing(&self, val: Option<f64>) -> Option<Option<usize>> {
+        if self.missing_policy != MissingPolicy::Error && self.missing_policy.is_missing(val) {
+            Some(self.no_data_index())
+        } else {
+            None
+        }
+    }
+}
+
+impl Deref for Classification {
+    type Target = [Bin];
+
+    fn deref(&self) -> &[Bin] {
+        &self.bins
+    }
+}
+
+impl<'a> IntoIterator for &'a Classification {
+    type Item = &'a Bin;
+    type IntoIter = std::slice::Iter<'a, Bin>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bins.iter()
+    }
+}
+
+impl IntoIterator for Classification {
+    type Item = Bin;
+    type IntoIter = std::vec::IntoIter<Bin>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.bins.into_iter()
+    }
+}
+
+impl PartialEq<Vec<Bin>> for Classification {
+    fn eq(&self, other: &Vec<Bin>) -> bool {
+        self.bins == *other
+    }
+}
+
+impl From<Vec<Bin>> for Classification {
+    /// Creates a Classification from bins alone, deriving the number of data points, range and closure from the bins
+    fn from(bins: Vec<Bin>) -> Self {
+        let num_vals: u64 = bins.iter().map(|bin| bin.count).sum();
+        let min = bins.first().map_or(f64::NAN, |bin| bin.bin_start);
+        let max = bins.last().map_or(f64::NAN, |bin| bin.bin_end);
+        let closure = bins.first().map_or(Closure::default(), |bin| bin.closure);
+        Classification {
+            bins,
+            method: None,
+            num_vals: num_vals as usize,
+            min,
+            max,
+            closure,
+            num_skipped: 0,
+            no_data_count: None,
+            missing_policy: MissingPolicy::Error,
+            fit: None,
+        }
+    }
+}
+
+impl From<Classification> for Vec<Bin> {
+    fn from(classification: Classification) -> Self {
+        classification.bins
+    }
+}
 
 /// Translates generic numeric vectors to Vec<f64> using the ToPrimitive trait from the num crate
 ///
 /// # Arguments
 ///
 /// * `data` - A reference to a vector of generic type T where T implements the ToPrimitive trait
+#[cfg(test)]
 pub fn to_vec_f64<T: ToPrimitive>(data: &[T]) -> Vec<f64> {
     let mut result: Vec<f64> = vec![];
     for item in data {
@@ -39,6 +348,191 @@ pub fn to_vec_f64<T: ToPrimitive>(data: &[T]) -> Vec<f64> {
     result
 }
 
+/// Translates generic numeric data to Vec<f64>, returning an error if the data is empty or if any value can't be converted or isn't finite
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of generic type T where T implements the ToPrimitive trait
+pub fn try_to_vec_f64<T: ToPrimitive>(data: &[T]) -> Result<Vec<f64>, ClassifyError> {
+    if data.is_empty() {
+        return Err(ClassifyError::EmptyInput);
+    }
+    let mut result: Vec<f64> = vec![];
+    for (i, item) in data.iter().enumerate() {
+        let val = item.to_f64().ok_or(ClassifyError::ConversionFailed(i))?;
+        if !val.is_finite() {
+            return Err(ClassifyError::NonFiniteValue(i));
+        }
+        result.push(val);
+    }
+    Ok(result)
+}
+
+/// Translates a generic numeric parameter to f64, returning an error if it can't be converted or isn't finite
+///
+/// # Arguments
+///
+/// * `name` - The name of the parameter, used in the error message
+/// * `param` - The parameter value to translate
+pub fn try_param_to_f64<S: ToPrimitive>(name: &str, param: S) -> Result<f64, ClassifyError> {
+    match param.to_f64() {
+        Some(val) if val.is_finite() => Ok(val),
+        _ => Err(ClassifyError::InvalidParameter(format!(
+            "{} must be a finite number",
+            name
+        ))),
+    }
+}
+
+/// Translates, sorts and aggregates generic numeric data and their weights into the unique values of the data along with their total weights, returning an error if the data is empty, if any value or weight can't be converted or isn't finite, if any weight is negative, if there isn't one weight per data point or if the weights are all zero
+///
+/// Unique values whose total weight is zero are left out
+///
+/// # Arguments
+///
+/// * `data` - A reference to a collection of unsorted data points
+/// * `weights` - A reference to a collection of weights, one per data point
+pub fn try_to_weighted_vals<T: ToPrimitive, W: ToPrimitive>(
+    data: &[T],
+    weights: &[W],
+) -> Result<Vec<WeightedVal>, ClassifyError> {
+    let data = try_to_vec_f64(data)?;
+    let weights = try_to_weights(weights, data.len())?;
+
+    let mut pairs: Vec<(f64, f64)> = data.into_iter().zip(weights).collect();
+    pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut weighted_vals: Vec<WeightedVal> = vec![];
+    for (val, weight) in pairs {
+        match weighted_vals.last_mut() {
+            Some(last) if last.val == val => last.weight += weight,
+            _ => weighted_vals.push(WeightedVal { val, weight }),
+        }
+    }
+    weighted_vals.retain(|item| item.weight > 0.0);
+
+    Ok(weighted_vals)
+}
+
+/// Translates generic numeric weights to Vec<f64>, returning an error if there isn't one weight per data point, if any weight can't be converted, isn't finite or is negative, or if the weights are all zero
+///
+/// # Arguments
+///
+/// * `weights` - A reference to a collection of weights
+/// * `num_vals` - The number of data points the weights belong to
+pub fn try_to_weights<W: ToPrimitive>(
+    weights: &[W],
+    num_vals: usize,
+) -> Result<Vec<f64>, ClassifyError> {
+    if weights.len() != num_vals {
+        return Err(ClassifyError::InvalidParameter(format!(
+            "expected {} weights (one per data point) but got {}",
+            num_vals,
+            weights.len()
+        )));
+    }
+    let mut result: Vec<f64> = vec![];
+    for (i, item) in weights.iter().enumerate() {
+        match item.to_f64() {
+            Some(weight) if weight.is_finite() && weight >= 0.0 => result.push(weight),
+            _ => {
+                return Err(ClassifyError::InvalidParameter(format!(
+                    "weight at index {} must be a finite, non-negative number",
+                    i
+                )))
+            }
+        }
+    }
+    if result.iter().all(|weight| *weight == 0.0) {
+        return Err(ClassifyError::InvalidParameter(
+            "weights must not all be zero".to_string(),
+        ));
+    }
+    Ok(result)
+}
+
+/// Represents the cumulative weights, sums and sums of squares of sorted data points (taken around a central value to limit cancellation), from which the sum of squared deviations of any range of points is calculated in constant time
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PrefixSums {
+    shift: f64,
+    weights: Option<Vec<f64>>,
+    sums: Vec<f64>,
+    sums_squares: Vec<f64>,
+}
+
+impl PrefixSums {
+    /// Returns the prefix sums of a non-empty collection of sorted data points
+    pub(crate) fn new(sorted_data: &[f64]) -> Self {
+        let shift = sorted_data[sorted_data.len() / 2];
+        let mut sums: Vec<f64> = vec![0.0; sorted_data.len() + 1];
+        let mut sums_squares: Vec<f64> = vec![0.0; sorted_data.len() + 1];
+        for (i, item) in sorted_data.iter().enumerate() {
+            let val = item - shift;
+            sums[i + 1] = sums[i] + val;
+            sums_squares[i + 1] = sums_squares[i] + val * val;
+        }
+        PrefixSums {
+            shift,
+            weights: None,
+            sums,
+            sums_squares,
+        }
+    }
+
+    /// Returns the prefix sums of a non-empty collection of sorted weighted unique values
+    pub(crate) fn from_weighted(weighted_vals: &[WeightedVal]) -> Self {
+        let shift = weighted_vals[weighted_vals.len() / 2].val;
+        let mut weights: Vec<f64> = vec![0.0; weighted_vals.len() + 1];
+        let mut sums: Vec<f64> = vec![0.0; weighted_vals.len() + 1];
+        let mut sums_squares: Vec<f64> = vec![0.0; weighted_vals.len() + 1];
+        for (i, item) in weighted_vals.iter().enumerate() {
+            let val = item.val - shift;
+            weights[i + 1] = weights[i] + item.weight;
+            sums[i + 1] = sums[i] + val * item.weight;
+            sums_squares[i + 1] = sums_squares[i] + val * val * item.weight;
+        }
+        PrefixSums {
+            shift,
+            weights: Some(weights),
+            sums,
+            sums_squares,
+        }
+    }
+
+    /// Returns the number of points (or weighted unique values) the prefix sums were taken over
+    pub(crate) fn num_points(&self) -> usize {
+        self.sums.len() - 1
+    }
+
+    /// Returns the (weighted) sum of squared deviations of the points first..end around their mean (0 for an empty range)
+    pub(crate) fn ssd(&self, first: usize, end: usize) -> f64 {
+        let weight = match &self.weights {
+            Some(weights) => weights[end] - weights[first],
+            None => (end - first) as f64,
+        };
+        if weight <= 0.0 {
+            return 0.0;
+        }
+        let sum = self.sums[end] - self.sums[first];
+        (self.sums_squares[end] - self.sums_squares[first] - sum * sum / weight).max(0.0)
+    }
+}
+
+/// Returns the unique values of a unique value map weighted by their number of occurrences
+///
+/// # Arguments
+///
+/// * `u_val_map` - A reference to a map of unique values to their first and last occurrences in the dataset
+pub fn unique_to_weighted_vals(u_val_map: &[UniqueVal]) -> Vec<WeightedVal> {
+    u_val_map
+        .iter()
+        .map(|item| WeightedVal {
+            val: item.val,
+            weight: (item.last - item.first + 1) as f64,
+        })
+        .collect()
+}
+
 /// Populates an empty vector of UniqueVal objects for each unique value in the dataset in the format (value, first occurrence index, last occurrence index)
 ///
 /// # Arguments
@@ -67,6 +561,9 @@ pub fn create_unique_val_mapping(unique_val_map: &mut Vec<UniqueVal>, vals: &[f6
             });
         }
     }
+    if let Some(last_unique_val) = unique_val_map.last_mut() {
+        last_unique_val.last = vals.len() - 1;
+    }
 }
 
 /// Adjusts break indices from unique value breaks to normal breaks, accounting for repeated values, given unique value breaks, a unique value map, and an empty vector for normal breaks
@@ -77,7 +574,7 @@ pub fn create_unique_val_mapping(unique_val_map: &mut Vec<UniqueVal>, vals: &[f6
 /// * `u_val_map` - A reference to a map of unique values to their first and last occurrences in the dataset
 /// * `normal_breaks` - A mutable reference to an empty vector to populate with adjusted break indices
 pub fn unique_to_normal_breaks(
-    u_val_breaks: &Vec<usize>,
+    u_val_breaks: &[usize],
     u_val_map: &[UniqueVal],
     normal_breaks: &mut Vec<usize>,
 ) {
@@ -96,6 +593,10 @@ pub fn unique_to_normal_breaks(
 /// * `breaks` - A reference to a vector of breaks (f64) generated through any classification function or manually
 /// * `data` - A reference to a vector of unsorted data points (f64) used to count the points in each bin
 ///
+/// # Edge Cases
+///
+/// * Panics if the data is empty or if any break or data point isn't finite; use try_breaks_to_classification to handle these cases instead
+///
 /// # Examples
 ///
 /// ```
@@ -106,20 +607,210 @@ pub fn unique_to_normal_breaks(
 /// let breaks: Vec<f64> = vec![2.0, 5.0];
 ///
 /// let result: Classification = breaks_to_classification(&breaks, &data);
-/// let expected: Classification = vec![
-///     Bin{bin_start: 1.0, bin_end: 2.0, count: 1},
-///     Bin{bin_start: 2.0, bin_end: 5.0, count: 2},
-///     Bin{bin_start: 5.0, bin_end: 8.0, count: 3}
+/// let expected: Vec<Bin> = vec![
+///     Bin::new(1.0, 2.0, 1),
+///     Bin::new(2.0, 5.0, 2),
+///     Bin::new(5.0, 8.0, 3)
 /// ];
 ///
 /// assert!(result == expected);
 /// ```
-pub fn breaks_to_classification<T: ToPrimitive>(breaks: &Vec<f64>, data: &[T]) -> Classification {
-    let data = to_vec_f64(data);
+pub fn breaks_to_classification<T: ToPrimitive>(breaks: &[f64], data: &[T]) -> Classification {
+    try_breaks_to_classification(breaks, data).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns a Classification object given a set of breaks between bins and the original dataset, or a ClassifyError if the data is empty or if any break or data point isn't finite
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a vector of breaks (f64) generated through any classification function or manually
+/// * `data` - A reference to a vector of unsorted data points (f64) used to count the points in each bin
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_breaks_to_classification};
+/// use classify::ClassifyError;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, f64::NAN, 5.0];
+/// let breaks: Vec<f64> = vec![2.0];
+///
+/// let result = try_breaks_to_classification(&breaks, &data);
+///
+/// assert_eq!(result, Err(ClassifyError::NonFiniteValue(2)));
+/// ```
+pub fn try_breaks_to_classification<T: ToPrimitive>(
+    breaks: &[f64],
+    data: &[T],
+) -> Result<Classification, ClassifyError> {
+    try_breaks_to_classification_with_closure(breaks, data, Closure::LeftClosed)
+}
+
+/// Returns a Classification object with the given closure given a set of breaks between bins and the original dataset, or a ClassifyError if the data is empty or if any break or data point isn't finite
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a vector of breaks (f64) generated through any classification function or manually
+/// * `data` - A reference to a vector of unsorted data points used to count the points in each bin
+/// * `closure` - Which end of the bins is closed, deciding the bin of data points equal to a break
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_breaks_to_classification_with_closure, Closure};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0, 7.0, 8.0];
+/// let breaks: Vec<f64> = vec![2.0, 5.0];
+///
+/// let left = try_breaks_to_classification_with_closure(&breaks, &data, Closure::LeftClosed).unwrap();
+/// let right = try_breaks_to_classification_with_closure(&breaks, &data, Closure::RightClosed).unwrap();
+///
+/// assert_eq!(left.iter().map(|bin| bin.count).collect::<Vec<u64>>(), vec![1, 2, 3]);
+/// assert_eq!(right.iter().map(|bin| bin.count).collect::<Vec<u64>>(), vec![2, 2, 2]);
+/// ```
+pub fn try_breaks_to_classification_with_closure<T: ToPrimitive>(
+    breaks: &[f64],
+    data: &[T],
+    closure: Closure,
+) -> Result<Classification, ClassifyError> {
+    let data = try_to_vec_f64(data)?;
+    calc_classification(breaks, &data, None, None, closure)
+}
+
+/// Returns a Classification object given a set of breaks between bins, the original dataset and the weight of every data point, or a ClassifyError if the input is invalid
+///
+/// Every bin records both its number of data points and their total weight, and the goodness-of-fit statistics are weighted
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a vector of breaks (f64) generated through any classification function or manually
+/// * `data` - A reference to a vector of unsorted data points used to count the points in each bin
+/// * `weights` - A reference to a collection of weights (e.g. populations), one per data point
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_breaks_to_classification_weighted;
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, 4.0, 5.0];
+/// let weights: Vec<f64> = vec![10.0, 30.0, 5.0, 5.0];
+///
+/// let result = try_breaks_to_classification_weighted(&[3.0], &data, &weights).unwrap();
+///
+/// assert_eq!(result.iter().map(|bin| bin.count).collect::<Vec<u64>>(), vec![2, 2]);
+/// assert_eq!(result.iter().map(|bin| bin.weight_sum).collect::<Vec<f64>>(), vec![40.0, 10.0]);
+/// assert!(try_breaks_to_classification_weighted(&[3.0], &data, &[1.0]).is_err());
+/// ```
+pub fn try_breaks_to_classification_weighted<T: ToPrimitive, W: ToPrimitive>(
+    breaks: &[f64],
+    data: &[T],
+    weights: &[W],
+) -> Result<Classification, ClassifyError> {
+    let data = try_to_vec_f64(data)?;
+    let weights = try_to_weights(weights, data.len())?;
+    calc_classification(breaks, &data, Some(&weights), None, Closure::LeftClosed)
+}
+
+/// Returns a Classification object given a set of breaks between bins and a histogram of (value, count) pairs, or a ClassifyError if the input is invalid
+///
+/// The bins' counts are the total counts of the histogram entries within them, so the data never has to be expanded
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a vector of breaks (f64) generated through any classification function or manually
+/// * `histogram` - A reference to a collection of (value, count) pairs in any order, where a value may appear more than once
+///
+/// # Examples
+///
+/// ```
+/// use classify::try_histogram_to_classification;
+///
+/// let histogram: Vec<(f64, u64)> = vec![(1.0, 1200), (2.0, 300), (5.0, 40), (9.0, 0)];
+///
+/// let result = try_histogram_to_classification(&[2.0], &histogram).unwrap();
+///
+/// assert_eq!(result.iter().map(|bin| bin.count).collect::<Vec<u64>>(), vec![1200, 340]);
+/// assert_eq!((result.num_vals, result.max), (1540, 5.0));
+/// ```
+pub fn try_histogram_to_classification(
+    breaks: &[f64],
+    histogram: &[(f64, u64)],
+) -> Result<Classification, ClassifyError> {
+    let (vals, counts) = try_split_histogram(histogram)?;
+    let weights: Vec<f64> = counts.iter().map(|count| *count as f64).collect();
+    calc_classification(
+        breaks,
+        &vals,
+        Some(&weights),
+        Some(&counts),
+        Closure::LeftClosed,
+    )
+}
+
+/// Splits a histogram of (value, count) pairs into its values and their counts, leaving out entries with a count of zero, returning an error if the histogram is empty, if any value isn't finite or if every count is zero
+///
+/// # Arguments
+///
+/// * `histogram` - A reference to a collection of (value, count) pairs
+pub fn try_split_histogram(
+    histogram: &[(f64, u64)],
+) -> Result<(Vec<f64>, Vec<u64>), ClassifyError> {
+    if histogram.is_empty() {
+        return Err(ClassifyError::EmptyInput);
+    }
+    let mut vals: Vec<f64> = vec![];
+    let mut counts: Vec<u64> = vec![];
+    for (i, (val, count)) in histogram.iter().enumerate() {
+        if !val.is_finite() {
+            return Err(ClassifyError::NonFiniteValue(i));
+        }
+        if *count > 0 {
+            vals.push(*val);
+            counts.push(*count);
+        }
+    }
+    if vals.is_empty() {
+        return Err(ClassifyError::InvalidParameter(
+            "histogram counts must not all be zero".to_string(),
+        ));
+    }
+    Ok((vals, counts))
+}
+
+/// Number of data points tallied together by a single task when counting the points in each bin
+const CLASSIFICATION_CHUNK_SIZE: usize = 1 << 16;
+
+/// Represents the per-bin counts and sums accumulated over one chunk of a dataset
+struct ChunkTally {
+    counts: Vec<u64>,
+    weight_sums: Vec<f64>,
+    dev_sums: Vec<f64>,
+    sdam: f64,
+}
+
+/// Returns a Classification object given a set of breaks, the translated dataset, the optional weight and number of occurrences of every data point and the closure of the bins, or a ClassifyError if any break isn't finite
+fn calc_classification(
+    breaks: &[f64],
+    data: &[f64],
+    weights: Option<&[f64]>,
+    counts: Option<&[u64]>,
+    closure: Closure,
+) -> Result<Classification, ClassifyError> {
+    if breaks.iter().any(|item| !item.is_finite()) {
+        return Err(ClassifyError::InvalidParameter(
+            "breaks must be finite numbers".to_string(),
+        ));
+    }
+    let weight = |i: usize| -> f64 { weights.map_or(1.0, |weights| weights[i]) };
+    let count = |i: usize| -> u64 { counts.map_or(1, |counts| counts[i]) };
 
     let mut min_value = data[0];
     let mut max_value = data[0];
-    for item in &data {
+    let mut mean = 0.0;
+    let mut total_weight = 0.0;
+    for (i, item) in data.iter().enumerate() {
+        mean += item * weight(i);
+        total_weight += weight(i);
         if *item < min_value {
             min_value = *item;
         }
@@ -127,6 +818,7 @@ pub fn breaks_to_classification<T: ToPrimitive>(breaks: &Vec<f64>, data: &[T]) -
             max_value = *item;
         }
     }
+    mean /= total_weight;
 
     let mut bounds: Vec<f64> = vec![min_value];
     for item in breaks {
@@ -134,26 +826,103 @@ pub fn breaks_to_classification<T: ToPrimitive>(breaks: &Vec<f64>, data: &[T]) -
     }
     bounds.push(max_value);
 
-    let mut results: Classification = vec![];
+    let mut bins: Vec<Bin> = vec![];
     for i in 0..(bounds.len() - 1) {
-        results.push(Bin {
+        bins.push(Bin {
             bin_start: bounds[i],
             bin_end: bounds[i + 1],
             count: 0,
+            weight_sum: 0.0,
+            closure,
         });
     }
 
-    let num_bins = results.len();
-    for bin in results.iter_mut().take(num_bins) {
-        for item in &data {
-            if bin.bin_start <= *item && *item < bin.bin_end {
-                bin.count += 1;
-            }
+    // Deviations are taken from the global mean to limit cancellation when computing the SDCM
+    // Points are tallied in fixed-size chunks whose partial sums are combined in order, so the result does not depend on whether the chunks are processed in parallel
+    let num_bins = bins.len();
+    let chunk_starts: Vec<usize> = (0..data.len()).step_by(CLASSIFICATION_CHUNK_SIZE).collect();
+    let partials = map_slice(&chunk_starts, |&start| {
+        let end = (start + CLASSIFICATION_CHUNK_SIZE).min(data.len());
+        let mut partial = ChunkTally {
+            counts: vec![0; num_bins],
+            weight_sums: vec![0.0; num_bins],
+            dev_sums: vec![0.0; num_bins],
+            sdam: 0.0,
+        };
+        for (i, item) in data.iter().enumerate().take(end).skip(start) {
+            let bin_idx = search_bins(&bins, *item, closure);
+            let dev = item - mean;
+            partial.sdam += dev * dev * weight(i);
+            partial.counts[bin_idx] += count(i);
+            partial.weight_sums[bin_idx] += weight(i);
+            partial.dev_sums[bin_idx] += dev * weight(i);
+        }
+        partial
+    });
+    let mut dev_sums: Vec<f64> = vec![0.0; num_bins];
+    let mut sdam: f64 = 0.0;
+    for partial in partials {
+        sdam += partial.sdam;
+        for j in 0..num_bins {
+            bins[j].count += partial.counts[j];
+            bins[j].weight_sum += partial.weight_sums[j];
+            dev_sums[j] += partial.dev_sums[j];
         }
     }
-    results[num_bins - 1].count += 1;
+    let mut sdcm: f64 = sdam;
+    for (bin, dev_sum) in bins.iter().zip(dev_sums.iter()) {
+        if bin.weight_sum > 0.0 {
+            sdcm -= dev_sum * dev_sum / bin.weight_sum;
+        }
+    }
+    let gvf = if sdam > 0.0 { 1.0 - sdcm / sdam } else { 1.0 };
+
+    Ok(Classification {
+        bins,
+        method: None,
+        num_vals: counts.map_or(data.len(), |counts| counts.iter().sum::<u64>() as usize),
+        min: min_value,
+        max: max_value,
+        closure,
+        num_skipped: 0,
+        no_data_count: None,
+        missing_policy: MissingPolicy::Error,
+        fit: Some(GoodnessOfFit { gvf, sdam, sdcm }),
+    })
+}
 
-    results
+/// Returns a Classification object given a set of breaks between bins and the original dataset, handling missing values according to a MissingPolicy, or a ClassifyError if the input is invalid
+///
+/// # Arguments
+///
+/// * `breaks` - A reference to a vector of breaks (f64) generated through any classification function or manually
+/// * `data` - A reference to a vector of unsorted data points used to count the points in each bin
+/// * `policy` - A reference to the MissingPolicy deciding which values are missing and how they are reported
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_breaks_to_classification_with_policy, MissingPolicy};
+///
+/// let data: Vec<f64> = vec![1.0, 2.0, -9999.0, 5.0, f64::NAN];
+/// let breaks: Vec<f64> = vec![2.0];
+/// let policy = MissingPolicy::NoDataBin(vec![-9999.0]);
+///
+/// let result = try_breaks_to_classification_with_policy(&breaks, &data, &policy).unwrap();
+///
+/// assert_eq!((result[0].count, result[1].count), (1, 2));
+/// assert_eq!(result.num_skipped, 2);
+/// assert_eq!(result.no_data_count, Some(2));
+/// ```
+pub fn try_breaks_to_classification_with_policy<T: ToPrimitive>(
+    breaks: &[f64],
+    data: &[T],
+    policy: &MissingPolicy,
+) -> Result<Classification, ClassifyError> {
+    let (values, num_skipped) = apply_missing_policy(data, policy)?;
+    let mut classification = try_breaks_to_classification(breaks, &values)?;
+    classification.set_missing(num_skipped, num_skipped as f64, policy);
+    Ok(classification)
 }
 
 /// Returns an Option<usize> containing the index of the Bin within which a value should fall given the value and a Classification (returns None if the value is outside of the Classification's range)
@@ -163,32 +932,147 @@ pub fn breaks_to_classification<T: ToPrimitive>(breaks: &Vec<f64>, data: &[T]) -
 /// * `val` - Data value to classify
 /// * `class` - Classification object
 ///
+/// # Edge Cases
+///
+/// * Values equal to a break fall in the bin above it if the Classification is left-closed and in the bin below it if it is right-closed
+/// * Values that are missing under the Classification's MissingPolicy are classified as its no-data bin if it has one, and as None otherwise
+/// * NaN is classified as None (or as the no-data bin if the Classification has one), as in classify_values
+/// * Panics if the Classification is empty; use try_classify_val to handle this case instead
+///
 /// # Examples
 ///
 /// ```
 /// use classify::{classify_val};
 /// use classify::{Classification, Bin};
 ///
-/// let vals: Vec<f64> = vec![0.0, 1.5, 3.5];
-/// let class: Classification = vec![
-///     Bin{bin_start: 0.0, bin_end: 1.0, count: 5},
-///     Bin{bin_start: 1.0, bin_end: 2.0, count: 5},
-///     Bin{bin_start: 2.0, bin_end: 3.0, count: 5}
-/// ];
+/// let vals: Vec<f64> = vec![0.0, 1.5, 3.5, f64::NAN];
+/// let class: Classification = Classification::from(vec![
+///     Bin::new(0.0, 1.0, 5),
+///     Bin::new(1.0, 2.0, 5),
+///     Bin::new(2.0, 3.0, 5)
+/// ]);
 ///
 /// let mut results: Vec<Option<usize>> = vec![];
 /// for val in vals {results.push(classify_val(val, &class))}
 ///
-/// assert_eq!(results, vec![Some(0), Some(1), None])
+/// assert_eq!(results, vec![Some(0), Some(1), None, None])
 /// ```
 pub fn classify_val(val: f64, class: &Classification) -> Option<usize> {
-    if val < class[0].bin_start || val > class[class.len() - 1].bin_end {
-        return None;
+    if val.is_nan() && !class.value_bins().is_empty() {
+        return class.classify_missing(Some(val)).flatten();
+    }
+    try_classify_val(val, class).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Returns the index of the Bin within which a value should fall given the value and a Classification (None if the value is outside of the Classification's range), or a ClassifyError if the Classification is empty or the value is NaN
+///
+/// # Arguments
+///
+/// * `val` - Data value to classify
+/// * `class` - Classification object
+///
+/// # Examples
+///
+/// ```
+/// use classify::{try_classify_val};
+/// use classify::{Classification, Bin, ClassifyError};
+///
+/// let class: Classification = Classification::from(vec![
+///     Bin::new(0.0, 1.0, 5),
+///     Bin::new(1.0, 2.0, 5)
+/// ]);
+///
+/// assert_eq!(try_classify_val(1.5, &class), Ok(Some(1)));
+/// assert!(matches!(try_classify_val(f64::NAN, &class), Err(ClassifyError::InvalidParameter(_))));
+/// assert_eq!(try_classify_val(1.5, &Classification::from(vec![])), Err(ClassifyError::EmptyInput));
+/// ```
+pub fn try_classify_val(val: f64, class: &Classification) -> Result<Option<usize>, ClassifyError> {
+    let bins = class.value_bins();
+    if bins.is_empty() {
+        return Err(ClassifyError::EmptyInput);
+    }
+    if let Some(result) = class.classify_missing(Some(val)) {
+        return Ok(result);
+    }
+    if val.is_nan() {
+        return Err(ClassifyError::InvalidParameter(
+            "value to classify must not be NaN".to_string(),
+        ));
+    }
+    if val < bins[0].bin_start || val > bins[bins.len() - 1].bin_end {
+        return Ok(None);
+    }
+    Ok(Some(search_bins(bins, val, class.closure)))
+}
+
+/// Returns the index of the Bin within which each value should fall given a collection of values and a Classification
+///
+/// Unlike classify_val, this never panics, and values are classified in parallel with the `parallel` feature
+///
+/// # Arguments
+///
+/// * `vals` - A reference to a collection of data values to classify
+/// * `class` - Classification object
+///
+/// # Edge Cases
+///
+/// * Values that are outside of the Classification's range, are NaN or can't be converted to f64 are classified as None
+/// * Values that are missing under the Classification's MissingPolicy are classified as its no-data bin if it has one, and as None otherwise
+/// * Values equal to a break fall in the bin above it if the Classification is left-closed and in the bin below it if it is right-closed
+///
+/// # Examples
+///
+/// ```
+/// use classify::{classify_values};
+/// use classify::{Classification, Bin};
+///
+/// let vals: Vec<f64> = vec![0.0, 1.5, 3.0, 3.5, f64::NAN];
+/// let class: Classification = Classification::from(vec![
+///     Bin::new(0.0, 1.0, 5),
+///     Bin::new(1.0, 2.0, 5),
+///     Bin::new(2.0, 3.0, 5)
+/// ]);
+///
+/// assert_eq!(classify_values(&vals, &class), vec![Some(0), Some(1), Some(2), None, None])
+/// ```
+pub fn classify_values<T: ToPrimitive>(vals: &[T], class: &Classification) -> Vec<Option<usize>> {
+    let bins = class.value_bins();
+    if bins.is_empty() {
+        return vec![None; vals.len()];
     }
-    for (i, _item) in class.iter().enumerate() {
-        if class[i].bin_start <= val && val < class[i].bin_end {
-            return Some(i);
+    let min_value = bins[0].bin_start;
+    let max_value = bins[bins.len() - 1].bin_end;
+
+    let classify = |item: Option<f64>| {
+        if let Some(result) = class.classify_missing(item) {
+            return result;
         }
+        match item {
+            Some(val) if min_value <= val && val <= max_value => {
+                Some(search_bins(bins, val, class.closure))
+            }
+            _ => None,
+        }
+    };
+
+    // Values are only converted up front when they are shared across threads, since `T` need not be `Sync`
+    #[cfg(feature = "parallel")]
+    {
+        let vals: Vec<Option<f64>> = vals.iter().map(|item| item.to_f64()).collect();
+        map_slice(&vals, |item| classify(*item))
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        vals.iter().map(|item| classify(item.to_f64())).collect()
+    }
+}
+
+/// Returns the index of the bin containing a value through binary search, given non-empty bins sorted in ascending order and their closure
+///
+/// Values equal to a break fall in the bin above it for left-closed bins and in the bin below it for right-closed bins
+fn search_bins(bins: &[Bin], val: f64, closure: Closure) -> usize {
+    match closure {
+        Closure::LeftClosed => bins[1..].partition_point(|bin| bin.bin_start <= val),
+        Closure::RightClosed => bins[1..].partition_point(|bin| bin.bin_start < val),
     }
-    Some(class.len() - 1) // Accounts for case where val is maximum within dataset
 }
@@ -1,4 +1,4 @@
-use crate::Bin;
+use crate::{Bin, Classification};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
@@ -30,43 +30,35 @@ impl PartialEq for JSClassification {
     }
 }
 
-impl From<Vec<Bin>> for JSClassification {
-    fn from(classification: Vec<Bin>) -> Self {
+impl From<Classification> for JSClassification {
+    fn from(classification: Classification) -> Self {
         JSClassification(classification.iter().map(|bin| JSBin::from(bin)).collect())
     }
 }
 
-impl From<JSClassification> for Vec<Bin> {
+impl From<JSClassification> for Classification {
     fn from(jsclassification: JSClassification) -> Self {
         let JSClassification(jsbins) = jsclassification;
         let mut result: Vec<Bin> = vec![];
 
         for bin in jsbins {
-            result.push(Bin {
-                bin_start: bin.bin_start,
-                bin_end: bin.bin_end,
-                count: bin.count,
-            });
+            result.push(Bin::new(bin.bin_start, bin.bin_end, bin.count));
         }
 
-        result
+        result.into()
     }
 }
 
-impl From<&JSClassification> for Vec<Bin> {
+impl From<&JSClassification> for Classification {
     fn from(jsclassification: &JSClassification) -> Self {
         let JSClassification(jsbins) = jsclassification;
         let mut result: Vec<Bin> = vec![];
 
         for bin in jsbins {
-            result.push(Bin {
-                bin_start: bin.bin_start,
-                bin_end: bin.bin_end,
-                count: bin.count,
-            });
+            result.push(Bin::new(bin.bin_start, bin.bin_end, bin.count));
         }
 
-        result
+        result.into()
     }
 }
 
@@ -82,8 +74,7 @@ impl From<&Bin> for JSBin {
 
 #[wasm_bindgen]
 pub fn breaks_to_classification(breaks: &[f64], data: &[f64]) -> JsValue {
-    let class: JSClassification =
-        crate::utilities::breaks_to_classification(&breaks.to_vec(), data).into();
+    let class: JSClassification = crate::utilities::breaks_to_classification(breaks, data).into();
     JsValue::from_serde(&class).unwrap()
 }
 
@@ -94,6 +85,13 @@ pub fn classify_val(val: f64, class: &JsValue) -> Option<usize> {
     bin
 }
 
+#[wasm_bindgen]
+pub fn classify_values(vals: &[f64], class: &JsValue) -> JsValue {
+    let class: JSClassification = class.into_serde().unwrap();
+    let bins: Vec<Option<usize>> = crate::utilities::classify_values(vals, &(class.into()));
+    JsValue::from_serde(&bins).unwrap()
+}
+
 #[wasm_bindgen]
 pub fn get_jenks_breaks(no_bins: usize, data: &[f64]) -> Box<[f64]> {
     let breaks = crate::jenks::get_jenks_breaks(no_bins, data);
@@ -106,6 +104,19 @@ pub fn get_jenks_classification(no_bins: usize, data: &[f64]) -> JsValue {
     JsValue::from_serde(&class).unwrap()
 }
 
+#[wasm_bindgen]
+pub fn get_approx_jenks_breaks(no_bins: usize, data: &[f64]) -> Box<[f64]> {
+    let breaks = crate::jenks::get_approx_jenks_breaks(no_bins, data);
+    breaks.into_boxed_slice()
+}
+
+#[wasm_bindgen]
+pub fn get_approx_jenks_classification(no_bins: usize, data: &[f64]) -> JsValue {
+    let class: JSClassification =
+        crate::jenks::get_approx_jenks_classification(no_bins, data).into();
+    JsValue::from_serde(&class).unwrap()
+}
+
 #[wasm_bindgen]
 pub fn get_quantile_breaks(no_bins: usize, data: &[f64]) -> Box<[f64]> {
     let breaks = crate::quantile::get_quantile_breaks(no_bins, data);